@@ -1,31 +1,42 @@
 use derive_builder::Builder;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+use crate::units::{ByteSize, Seconds};
 
 /// Soft and/or hard resource limits to be imposed on a job.
-#[derive(Builder, Clone, Deserialize, Serialize)]
+#[derive(Builder, Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "PascalCase")]
-#[builder(setter(into, strip_option))]
+#[builder(default, setter(into, strip_option), build_fn(error = "crate::Error"))]
 pub struct ResourceLimits {
     /// The largest size (in bytes) core file that may be created.
-    pub core: Option<u32>,
+    #[builder(try_setter)]
+    pub core: Option<ByteSize>,
 
     /// The maximum amount of cpu time (in seconds) to be used by each
     /// process.
+    ///
+    /// The builder also accepts a [`Duration`](std::time::Duration) through
+    /// `try_cpu`.
     #[serde(rename = "CPU")]
-    pub cpu: Option<u32>,
+    #[builder(try_setter)]
+    pub cpu: Option<Seconds>,
 
     /// The maximum size (in bytes) of the data segment for a process.
     ///
     /// This defines how far a program may extend its break with the `sbrk(2)`
     /// system call.
-    pub data: Option<u32>,
+    #[builder(try_setter)]
+    pub data: Option<ByteSize>,
 
     /// The largest size (in bytes) file that may be created.
-    pub file_size: Option<u32>,
+    #[builder(try_setter)]
+    pub file_size: Option<ByteSize>,
 
     /// The maximum size (in bytes) which a process may lock into memory
     /// using the `mlock(2)` function.
-    pub memory_lock: Option<u32>,
+    #[builder(try_setter)]
+    pub memory_lock: Option<ByteSize>,
 
     /// The maximum number of open files for this process.
     ///
@@ -35,7 +46,7 @@ pub struct ResourceLimits {
     /// or `kern.maxfilesperproc`
     /// ([`hard_resource_limits`](crate::LaunchAgent::hard_resource_limits))
     /// value in addition to the `setrlimit(2)` values.
-    pub number_of_files: Option<u32>,
+    pub number_of_files: Option<u64>,
 
     /// The maximum number of simultaneous processes for this UID.
     ///
@@ -45,7 +56,7 @@ pub struct ResourceLimits {
     /// or `kern.maxprocperuid`
     /// ([`hard_resource_limits`](crate::LaunchAgent::hard_resource_limits))
     /// value in addition to the `setrlimit(2)` values.
-    pub number_of_processes: Option<u32>,
+    pub number_of_processes: Option<u64>,
 
     /// The maximum size (in bytes) to which a process's resident set size
     /// may grow.
@@ -53,25 +64,168 @@ pub struct ResourceLimits {
     /// This imposes a limit on the amount of physical memory to be given to a
     /// process; if memory is tight, the system will prefer to take memory from
     /// processes that are exceeding their declared resident set size.
-    pub resident_set_size: Option<u32>,
+    #[builder(try_setter)]
+    pub resident_set_size: Option<ByteSize>,
 
     /// The maximum size (in bytes) of the stack segment for a process.
     ///
     /// This defines how far a program's stack segment may be extended.
     /// Stack extension is performed automatically by the system.
-    pub stack: Option<u32>,
+    #[builder(try_setter)]
+    pub stack: Option<ByteSize>,
+}
+
+impl ResourceLimits {
+    /// Each limit as a plain integer, paired with its property list key.
+    pub(crate) fn entries(&self) -> [(&'static str, Option<u64>); 9] {
+        [
+            ("Core", self.core.map(u64::from)),
+            ("CPU", self.cpu.map(u64::from)),
+            ("Data", self.data.map(u64::from)),
+            ("FileSize", self.file_size.map(u64::from)),
+            ("MemoryLock", self.memory_lock.map(u64::from)),
+            ("NumberOfFiles", self.number_of_files),
+            ("NumberOfProcesses", self.number_of_processes),
+            ("ResidentSetSize", self.resident_set_size.map(u64::from)),
+            ("Stack", self.stack.map(u64::from)),
+        ]
+    }
+
+    /// Lowers every limit that is above the matching limit in `other`.
+    pub(crate) fn clamp_to(&mut self, other: &Self) {
+        fn clamp<T: Copy + Ord>(value: &mut Option<T>, max: Option<T>) {
+            if let (Some(value), Some(max)) = (value.as_mut(), max) {
+                *value = (*value).min(max);
+            }
+        }
+
+        clamp(&mut self.core, other.core);
+        clamp(&mut self.cpu, other.cpu);
+        clamp(&mut self.data, other.data);
+        clamp(&mut self.file_size, other.file_size);
+        clamp(&mut self.memory_lock, other.memory_lock);
+        clamp(&mut self.number_of_files, other.number_of_files);
+        clamp(&mut self.number_of_processes, other.number_of_processes);
+        clamp(&mut self.resident_set_size, other.resident_set_size);
+        clamp(&mut self.stack, other.stack);
+    }
 }
 
 /// The type of session a job may be run in.
-#[derive(Clone, Deserialize, Serialize)]
-#[serde(untagged)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum SessionType {
-    Single(String),
-    Many(Vec<String>),
+    /// The GUI session of a logged-in user. This is the default for agents.
+    Aqua,
+
+    /// A non-GUI session that exists for the lifetime of the user's login,
+    /// such as the one `launchctl bsexec` runs in.
+    Background,
+
+    /// The session that exists while the login window is displayed, before a
+    /// user logs in.
+    LoginWindow,
+
+    /// A non-GUI session created for an SSH login.
+    StandardIO,
+
+    /// The privileged system session, used for daemons.
+    System,
+
+    /// A session type not documented in `launchd.plist(5)`.
+    Other(String),
+}
+
+impl SessionType {
+    /// Every documented session type.
+    pub const KNOWN: [SessionType; 5] = [
+        SessionType::Aqua,
+        SessionType::Background,
+        SessionType::LoginWindow,
+        SessionType::StandardIO,
+        SessionType::System,
+    ];
+
+    /// The name of the session type as it appears in a property list.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Aqua => "Aqua",
+            Self::Background => "Background",
+            Self::LoginWindow => "LoginWindow",
+            Self::StandardIO => "StandardIO",
+            Self::System => "System",
+            Self::Other(name) => name,
+        }
+    }
+}
+
+impl From<&str> for SessionType {
+    /// Session type names are case-sensitive, so anything that isn't spelled
+    /// exactly as documented becomes [`Other`](SessionType::Other).
+    fn from(name: &str) -> Self {
+        Self::KNOWN
+            .into_iter()
+            .find(|known| known.as_str() == name)
+            .unwrap_or_else(|| Self::Other(name.to_string()))
+    }
+}
+
+impl fmt::Display for SessionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for SessionType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for SessionType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|name| Self::from(name.as_str()))
+    }
+}
+
+/// One or more types of session a job may be run in.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum SessionTypes {
+    Single(SessionType),
+    Many(Vec<SessionType>),
+}
+
+impl SessionTypes {
+    /// Every session type listed.
+    pub fn as_slice(&self) -> &[SessionType] {
+        match self {
+            Self::Single(session_type) => std::slice::from_ref(session_type),
+            Self::Many(session_types) => session_types,
+        }
+    }
+
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [SessionType] {
+        match self {
+            Self::Single(session_type) => std::slice::from_mut(session_type),
+            Self::Many(session_types) => session_types,
+        }
+    }
+}
+
+impl From<SessionType> for SessionTypes {
+    fn from(session_type: SessionType) -> Self {
+        Self::Single(session_type)
+    }
+}
+
+impl From<Vec<SessionType>> for SessionTypes {
+    fn from(session_types: Vec<SessionType>) -> Self {
+        Self::Many(session_types)
+    }
 }
 
 /// The intended purpose of a job.
-#[derive(Clone, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum ProcessType {
     /// Background jobs are generally processes that do work that was not
     /// directly requested by the user.
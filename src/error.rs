@@ -0,0 +1,169 @@
+use derive_builder::UninitializedFieldError;
+use std::{error, fmt, io, path::PathBuf};
+
+use crate::{schedule::ScheduleError, validation::ValidationError};
+
+/// A specialized [`Result`](std::result::Result) type for this crate.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Everything that can go wrong while building, reading or writing a
+/// [`LaunchAgent`](crate::LaunchAgent).
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Reading or writing failed, for example because of missing permissions.
+    Io {
+        /// The file being accessed, if the I/O was file-based.
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+
+    /// A string could not be parsed into a typed value.
+    InvalidValue {
+        value: String,
+
+        /// A description of what was expected instead.
+        expected: &'static str,
+    },
+
+    /// The job could not be encoded as a property list.
+    Serialize(plist::Error),
+
+    /// The input was not a property list describing a job.
+    Deserialize {
+        /// The file being read, if the input came from a file.
+        path: Option<PathBuf>,
+
+        /// The dot-separated key that failed to parse, such as
+        /// `KeepAlive.SuccessfulExit`. Empty if the input was not a valid
+        /// property list at all.
+        key_path: String,
+        source: plist::Error,
+    },
+
+    /// The job breaks one or more rules that `launchd` enforces.
+    Validation(ValidationError),
+
+    /// The property list contains keys that are not modeled by this crate,
+    /// which is only an error when reading in strict mode.
+    UnknownKeys {
+        /// The file being read, if the input came from a file.
+        path: Option<PathBuf>,
+
+        /// The dot-separated key path of each unknown key, such as
+        /// `KeepAlive.AfterInitialDemand`.
+        keys: Vec<String>,
+    },
+
+    /// Two settings cannot be used together.
+    Conflict {
+        /// The dot-separated key that conflicts.
+        key_path: String,
+        message: String,
+    },
+
+    /// A schedule expression, such as a crontab line, could not be converted
+    /// into calendar intervals.
+    Schedule {
+        expression: String,
+        source: ScheduleError,
+    },
+}
+
+impl Error {
+    /// Attaches the path of the file being accessed to the error.
+    pub(crate) fn at_path(self, path: impl Into<PathBuf>) -> Self {
+        match self {
+            Self::Io { path: None, source } => Self::Io {
+                path: Some(path.into()),
+                source,
+            },
+            Self::Deserialize {
+                path: None,
+                key_path,
+                source,
+            } => Self::Deserialize {
+                path: Some(path.into()),
+                key_path,
+                source,
+            },
+            Self::UnknownKeys { path: None, keys } => Self::UnknownKeys {
+                path: Some(path.into()),
+                keys,
+            },
+            error => error,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io {
+                path: Some(path),
+                source,
+            } => write!(f, "failed to access {path:?}: {source}"),
+            Self::Io { path: None, source } => write!(f, "I/O error: {source}"),
+            Self::InvalidValue { value, expected } => {
+                write!(f, "invalid value {value:?}: expected {expected}")
+            }
+            Self::Serialize(source) => write!(f, "failed to serialize LaunchAgent: {source}"),
+            Self::Deserialize {
+                path,
+                key_path,
+                source,
+            } => {
+                write!(f, "failed to parse LaunchAgent")?;
+                if let Some(path) = path {
+                    write!(f, " from {path:?}")?;
+                }
+                if !key_path.is_empty() {
+                    write!(f, " at {key_path}")?;
+                }
+                write!(f, ": {source}")
+            }
+            Self::Validation(error) => error.fmt(f),
+            Self::UnknownKeys { path, keys } => {
+                write!(f, "unknown keys")?;
+                if let Some(path) = path {
+                    write!(f, " in {path:?}")?;
+                }
+                write!(f, ": {}", keys.join(", "))
+            }
+            Self::Conflict { key_path, message } => write!(f, "conflict at {key_path}: {message}"),
+            Self::Schedule { expression, source } => {
+                write!(f, "invalid schedule {expression:?}: {source}")
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Serialize(source) | Self::Deserialize { source, .. } => Some(source),
+            Self::Validation(error) => Some(error),
+            Self::Schedule { source, .. } => Some(source),
+            Self::InvalidValue { .. } | Self::UnknownKeys { .. } | Self::Conflict { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(source: io::Error) -> Self {
+        Self::Io { path: None, source }
+    }
+}
+
+impl From<ValidationError> for Error {
+    fn from(error: ValidationError) -> Self {
+        Self::Validation(error)
+    }
+}
+
+impl From<UninitializedFieldError> for Error {
+    fn from(error: UninitializedFieldError) -> Self {
+        Self::Validation(error.into())
+    }
+}
@@ -0,0 +1,188 @@
+use derive_builder::Builder;
+use plist::Value;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[cfg(test)]
+mod tests;
+
+/// A single event descriptor: a dictionary whose keys and values are
+/// specific to the event stream it belongs to.
+pub type EventDescriptor = BTreeMap<String, Value>;
+
+/// Higher-level event sources used to launch a job on demand, keyed by event
+/// stream (such as `com.apple.iokit.matching`) and then by descriptor name.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct LaunchEvents(BTreeMap<String, BTreeMap<String, EventDescriptor>>);
+
+impl LaunchEvents {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a typed descriptor named `name` to the stream it belongs to.
+    pub fn insert<E: LaunchEvent>(&mut self, name: &str, event: E) -> &mut Self {
+        self.insert_descriptor(E::STREAM, name, event.into_descriptor())
+    }
+
+    /// Adds a raw descriptor named `name` to `stream`, replacing any existing
+    /// descriptor with the same name.
+    pub fn insert_descriptor(
+        &mut self,
+        stream: &str,
+        name: &str,
+        descriptor: EventDescriptor,
+    ) -> &mut Self {
+        self.0
+            .entry(stream.to_string())
+            .or_default()
+            .insert(name.to_string(), descriptor);
+        self
+    }
+
+    /// Like [`insert`](Self::insert), but consumes and returns `self` so calls
+    /// can be chained while building a [`LaunchAgent`](crate::LaunchAgent).
+    pub fn with<E: LaunchEvent>(mut self, name: &str, event: E) -> Self {
+        self.insert(name, event);
+        self
+    }
+
+    /// The descriptor named `name` in `stream`, if there is one.
+    pub fn get(&self, stream: &str, name: &str) -> Option<&EventDescriptor> {
+        self.0.get(stream)?.get(name)
+    }
+
+    /// Every descriptor, grouped by stream.
+    pub fn streams(&self) -> &BTreeMap<String, BTreeMap<String, EventDescriptor>> {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<BTreeMap<String, BTreeMap<String, EventDescriptor>>> for LaunchEvents {
+    fn from(streams: BTreeMap<String, BTreeMap<String, EventDescriptor>>) -> Self {
+        Self(streams)
+    }
+}
+
+/// A typed descriptor for a well-known event stream.
+pub trait LaunchEvent {
+    /// The event stream the descriptor belongs to.
+    const STREAM: &'static str;
+
+    /// Converts the descriptor into the dictionary `launchd` expects.
+    fn into_descriptor(self) -> EventDescriptor;
+}
+
+/// Launches the job when a node matching the descriptor appears in the
+/// IORegistry, such as when a USB device is connected.
+///
+/// See `xpc_events(3)` and `IOKitLib.h` for the matching keys.
+#[derive(Builder, Clone, Debug, Default, PartialEq)]
+#[builder(default, setter(into, strip_option), build_fn(error = "crate::Error"))]
+pub struct IoKitMatching {
+    /// The IOKit class to match, such as `IOUSBHostDevice`.
+    pub provider_class: Option<String>,
+
+    /// The USB vendor ID to match.
+    pub vendor_id: Option<i64>,
+
+    /// The USB product ID to match.
+    pub product_id: Option<i64>,
+
+    /// Whether a match should launch the job.
+    ///
+    /// Defaults to `true`, since without it matches are only delivered to a
+    /// job that is already running.
+    pub match_launch_stream: Option<bool>,
+
+    /// Any other IOKit matching keys, such as `IOPropertyMatch`.
+    #[builder(setter(into = false, strip_option = false))]
+    pub properties: EventDescriptor,
+}
+
+impl LaunchEvent for IoKitMatching {
+    const STREAM: &'static str = "com.apple.iokit.matching";
+
+    fn into_descriptor(self) -> EventDescriptor {
+        let mut descriptor = self.properties;
+
+        if let Some(provider_class) = self.provider_class {
+            descriptor.insert("IOProviderClass".to_string(), provider_class.into());
+        }
+        if let Some(vendor_id) = self.vendor_id {
+            descriptor.insert("idVendor".to_string(), vendor_id.into());
+        }
+        if let Some(product_id) = self.product_id {
+            descriptor.insert("idProduct".to_string(), product_id.into());
+        }
+        descriptor.insert(
+            "IOMatchLaunchStream".to_string(),
+            self.match_launch_stream.unwrap_or(true).into(),
+        );
+
+        descriptor
+    }
+}
+
+/// Launches the job when a `notify(3)` notification is posted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NotifydMatching {
+    /// The name of the notification, such as
+    /// `com.apple.system.timezone`.
+    pub notification: String,
+}
+
+impl NotifydMatching {
+    pub fn new(notification: &str) -> Self {
+        Self {
+            notification: notification.to_string(),
+        }
+    }
+}
+
+impl LaunchEvent for NotifydMatching {
+    const STREAM: &'static str = "com.apple.notifyd.matching";
+
+    fn into_descriptor(self) -> EventDescriptor {
+        BTreeMap::from([("Notification".to_string(), self.notification.into())])
+    }
+}
+
+/// Launches the job when a distributed notification is posted through
+/// `NSDistributedNotificationCenter`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistnotedMatching {
+    /// The name of the notification.
+    pub name: String,
+
+    /// The object the notification must be posted with, if any.
+    pub object: Option<String>,
+}
+
+impl DistnotedMatching {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            object: None,
+        }
+    }
+}
+
+impl LaunchEvent for DistnotedMatching {
+    const STREAM: &'static str = "com.apple.distnoted.matching";
+
+    fn into_descriptor(self) -> EventDescriptor {
+        let mut descriptor = BTreeMap::from([("Name".to_string(), self.name.into())]);
+
+        if let Some(object) = self.object {
+            descriptor.insert("Object".to_string(), object.into());
+        }
+
+        descriptor
+    }
+}
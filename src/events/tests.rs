@@ -0,0 +1,77 @@
+use super::*;
+use crate::{LaunchAgent, LaunchAgentBuilder};
+
+#[test]
+fn iokit_matching_builds_a_typed_descriptor() {
+    let events = LaunchEvents::new().with(
+        "com.example.device-attached",
+        IoKitMatchingBuilder::default()
+            .provider_class("IOUSBHostDevice")
+            .vendor_id(0x05ac)
+            .product_id(0x12a8)
+            .build()
+            .unwrap(),
+    );
+
+    let descriptor = events
+        .get("com.apple.iokit.matching", "com.example.device-attached")
+        .unwrap();
+
+    assert_eq!(
+        descriptor["IOProviderClass"],
+        Value::from("IOUSBHostDevice")
+    );
+    assert_eq!(descriptor["idVendor"], Value::from(0x05ac));
+    assert_eq!(descriptor["idProduct"], Value::from(0x12a8));
+    assert_eq!(descriptor["IOMatchLaunchStream"], Value::Boolean(true));
+}
+
+#[test]
+fn notification_streams_use_their_own_keys() {
+    let mut distnoted = DistnotedMatching::new("com.example.changed");
+    distnoted.object = Some("com.example.app".to_string());
+    let mut events = LaunchEvents::new();
+    events
+        .insert(
+            "timezone",
+            NotifydMatching::new("com.apple.system.timezone"),
+        )
+        .insert("changed", distnoted);
+
+    assert_eq!(
+        events
+            .get("com.apple.notifyd.matching", "timezone")
+            .unwrap()["Notification"],
+        Value::from("com.apple.system.timezone")
+    );
+    assert_eq!(
+        events
+            .get("com.apple.distnoted.matching", "changed")
+            .unwrap()["Object"],
+        Value::from("com.example.app")
+    );
+}
+
+#[test]
+fn non_string_leaves_round_trip() {
+    let agent = LaunchAgentBuilder::default()
+        .label("com.example.test")
+        .program("/usr/bin/example")
+        .launch_events(
+            LaunchEvents::new().with(
+                "device",
+                IoKitMatchingBuilder::default()
+                    .provider_class("IOUSBHostDevice")
+                    .vendor_id(1452)
+                    .build()
+                    .unwrap(),
+            ),
+        )
+        .build()
+        .unwrap();
+
+    let xml = agent.to_xml_string().unwrap();
+
+    assert!(xml.contains("<key>idVendor</key>\n\t\t\t\t<integer>1452</integer>"));
+    assert_eq!(xml.parse::<LaunchAgent>().unwrap(), agent);
+}
@@ -0,0 +1,10 @@
+/// The encoding used when writing a property list.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// The XML encoding written by Xcode and `plutil -convert xml1`.
+    #[default]
+    Xml,
+
+    /// The binary encoding written by `plutil -convert binary1`.
+    Binary,
+}
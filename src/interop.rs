@@ -0,0 +1,11 @@
+//! Conversions to and from the types of popular date and time crates, each
+//! behind a feature of the same name.
+
+#[cfg(feature = "chrono")]
+mod chrono;
+
+#[cfg(feature = "time")]
+mod time;
+
+#[cfg(all(test, any(feature = "chrono", feature = "time")))]
+mod tests;
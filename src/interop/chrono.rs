@@ -0,0 +1,91 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+use crate::{CalendarInterval, Month, Weekday, error::Error, schedule::DateTime};
+
+impl From<chrono::Weekday> for Weekday {
+    fn from(weekday: chrono::Weekday) -> Self {
+        Self::ALL[weekday.num_days_from_sunday() as usize]
+    }
+}
+
+impl From<Weekday> for chrono::Weekday {
+    fn from(weekday: Weekday) -> Self {
+        match weekday {
+            Weekday::Sunday => Self::Sun,
+            Weekday::Monday => Self::Mon,
+            Weekday::Tuesday => Self::Tue,
+            Weekday::Wednesday => Self::Wed,
+            Weekday::Thursday => Self::Thu,
+            Weekday::Friday => Self::Fri,
+            Weekday::Saturday => Self::Sat,
+        }
+    }
+}
+
+impl From<chrono::Month> for Month {
+    fn from(month: chrono::Month) -> Self {
+        Self::ALL[month.number_from_month() as usize - 1]
+    }
+}
+
+impl From<Month> for chrono::Month {
+    fn from(month: Month) -> Self {
+        Self::try_from(month.number()).expect("every month exists in chrono")
+    }
+}
+
+impl From<NaiveDateTime> for DateTime {
+    fn from(datetime: NaiveDateTime) -> Self {
+        DateTime::new(
+            datetime.year(),
+            datetime.month() as u8,
+            datetime.day() as u8,
+            datetime.hour() as u8,
+            datetime.minute() as u8,
+            datetime.second() as u8,
+        )
+        .expect("chrono only represents valid dates")
+    }
+}
+
+impl TryFrom<DateTime> for NaiveDateTime {
+    type Error = Error;
+
+    /// Fails for years outside of the range the `chrono` crate supports.
+    fn try_from(datetime: DateTime) -> Result<Self, Self::Error> {
+        NaiveDate::from_ymd_opt(
+            datetime.year(),
+            datetime.month().into(),
+            datetime.day().into(),
+        )
+        .and_then(|date| {
+            date.and_hms_opt(
+                datetime.hour().into(),
+                datetime.minute().into(),
+                datetime.second().into(),
+            )
+        })
+        .ok_or_else(|| Error::InvalidValue {
+            value: datetime.to_string(),
+            expected: "a date supported by the chrono crate",
+        })
+    }
+}
+
+impl CalendarInterval {
+    /// Runs at `time` on each of `weekdays`, or every day if `weekdays` is
+    /// empty, failing if `time` is not on a whole minute.
+    pub fn at_naive_time(
+        time: NaiveTime,
+        weekdays: impl IntoIterator<Item = impl Into<Weekday>>,
+    ) -> Result<Vec<Self>, Error> {
+        if time.second() != 0 || time.nanosecond() != 0 {
+            return Err(Error::InvalidValue {
+                value: time.to_string(),
+                expected: "a time on a whole minute",
+            });
+        }
+
+        Self::at(time.hour(), time.minute(), weekdays)
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{CalendarInterval, Month, Weekday, schedule::DateTime};
+
+#[cfg(feature = "chrono")]
+#[test]
+fn converts_chrono_types() {
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+    assert_eq!(Weekday::from(chrono::Weekday::Sun), Weekday::Sunday);
+    assert_eq!(
+        chrono::Weekday::from(Weekday::Saturday),
+        chrono::Weekday::Sat
+    );
+    assert_eq!(Month::from(chrono::Month::December), Month::December);
+    assert_eq!(chrono::Month::from(Month::January), chrono::Month::January);
+
+    let naive = NaiveDate::from_ymd_opt(2024, 2, 29)
+        .unwrap()
+        .and_hms_opt(9, 30, 0)
+        .unwrap();
+    let datetime = DateTime::from(naive);
+    assert_eq!(datetime, "2024-02-29 09:30".parse().unwrap());
+    assert_eq!(NaiveDateTime::try_from(datetime).unwrap(), naive);
+
+    let far_future = DateTime::new(300_000, 1, 1, 0, 0, 0).unwrap();
+    assert!(NaiveDateTime::try_from(far_future).is_err());
+
+    let time = NaiveTime::from_hms_opt(9, 30, 0).unwrap();
+    let intervals =
+        CalendarInterval::at_naive_time(time, [chrono::Weekday::Mon, chrono::Weekday::Wed])
+            .unwrap();
+    assert_eq!(
+        intervals,
+        CalendarInterval::at(9, 30, [Weekday::Monday, Weekday::Wednesday]).unwrap()
+    );
+
+    let late = NaiveTime::from_hms_opt(9, 30, 15).unwrap();
+    assert!(CalendarInterval::at_naive_time(late, [chrono::Weekday::Mon]).is_err());
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn converts_time_types() {
+    use time::{Date, PrimitiveDateTime, Time};
+
+    assert_eq!(Weekday::from(time::Weekday::Sunday), Weekday::Sunday);
+    assert_eq!(time::Weekday::from(Weekday::Monday), time::Weekday::Monday);
+    assert_eq!(Month::from(time::Month::May), Month::May);
+    assert_eq!(time::Month::from(Month::May), time::Month::May);
+
+    let primitive = PrimitiveDateTime::new(
+        Date::from_calendar_date(2024, time::Month::December, 31).unwrap(),
+        Time::from_hms(23, 59, 0).unwrap(),
+    );
+    let datetime = DateTime::from(primitive);
+    assert_eq!(datetime, "2024-12-31 23:59".parse().unwrap());
+    assert_eq!(PrimitiveDateTime::try_from(datetime).unwrap(), primitive);
+
+    let intervals =
+        CalendarInterval::at_time(Time::from_hms(6, 0, 0).unwrap(), [time::Weekday::Friday])
+            .unwrap();
+    assert_eq!(
+        intervals,
+        CalendarInterval::at(6, 0, [Weekday::Friday]).unwrap()
+    );
+    assert!(
+        CalendarInterval::at_time(Time::from_hms(6, 0, 1).unwrap(), [Weekday::Friday]).is_err()
+    );
+}
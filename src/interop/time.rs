@@ -0,0 +1,86 @@
+use time::{Date, PrimitiveDateTime, Time};
+
+use crate::{CalendarInterval, Month, Weekday, error::Error, schedule::DateTime};
+
+impl From<time::Weekday> for Weekday {
+    fn from(weekday: time::Weekday) -> Self {
+        Self::ALL[usize::from(weekday.number_days_from_sunday())]
+    }
+}
+
+impl From<Weekday> for time::Weekday {
+    fn from(weekday: Weekday) -> Self {
+        match weekday {
+            Weekday::Sunday => Self::Sunday,
+            Weekday::Monday => Self::Monday,
+            Weekday::Tuesday => Self::Tuesday,
+            Weekday::Wednesday => Self::Wednesday,
+            Weekday::Thursday => Self::Thursday,
+            Weekday::Friday => Self::Friday,
+            Weekday::Saturday => Self::Saturday,
+        }
+    }
+}
+
+impl From<time::Month> for Month {
+    fn from(month: time::Month) -> Self {
+        Self::ALL[usize::from(u8::from(month)) - 1]
+    }
+}
+
+impl From<Month> for time::Month {
+    fn from(month: Month) -> Self {
+        Self::try_from(month.number()).expect("every month exists in time")
+    }
+}
+
+impl From<PrimitiveDateTime> for DateTime {
+    fn from(datetime: PrimitiveDateTime) -> Self {
+        DateTime::new(
+            datetime.year(),
+            datetime.month().into(),
+            datetime.day(),
+            datetime.hour(),
+            datetime.minute(),
+            datetime.second(),
+        )
+        .expect("time only represents valid dates")
+    }
+}
+
+impl TryFrom<DateTime> for PrimitiveDateTime {
+    type Error = Error;
+
+    /// Fails for years outside of the range the `time` crate supports.
+    fn try_from(datetime: DateTime) -> Result<Self, Self::Error> {
+        let month = time::Month::from(Month::try_from(datetime.month())?);
+        let date = Date::from_calendar_date(datetime.year(), month, datetime.day());
+        let time = Time::from_hms(datetime.hour(), datetime.minute(), datetime.second());
+
+        match (date, time) {
+            (Ok(date), Ok(time)) => Ok(PrimitiveDateTime::new(date, time)),
+            _ => Err(Error::InvalidValue {
+                value: datetime.to_string(),
+                expected: "a date supported by the time crate",
+            }),
+        }
+    }
+}
+
+impl CalendarInterval {
+    /// Runs at `time` on each of `weekdays`, or every day if `weekdays` is
+    /// empty, failing if `time` is not on a whole minute.
+    pub fn at_time(
+        time: Time,
+        weekdays: impl IntoIterator<Item = impl Into<Weekday>>,
+    ) -> Result<Vec<Self>, Error> {
+        if time.second() != 0 || time.nanosecond() != 0 {
+            return Err(Error::InvalidValue {
+                value: time.to_string(),
+                expected: "a time on a whole minute",
+            });
+        }
+
+        Self::at(time.hour().into(), time.minute().into(), weekdays)
+    }
+}
@@ -1,9 +1,9 @@
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
-use crate::{defaults::default_false, unions::StringOrU32};
+use crate::{defaults::default_false, modes::FileMode, unions::StringOrU32};
 
-#[derive(Clone, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct InetdCompatibility {
     /// Corresponds to the "wait" or "nowait" option of `inetd`.
@@ -14,7 +14,7 @@ pub struct InetdCompatibility {
     pub wait: Option<bool>,
 }
 
-#[derive(Clone, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(untagged)]
 pub enum MachService {
     Bool(bool),
@@ -54,16 +54,16 @@ pub enum MachService {
     },
 }
 
-#[derive(Clone, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(untagged)]
 pub enum SocketValue {
     Single(Socket),
     Many(Vec<Socket>),
 }
 
-#[derive(Builder, Clone, Deserialize, Serialize)]
+#[derive(Builder, Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "PascalCase")]
-#[builder(setter(into, strip_option))]
+#[builder(setter(into, strip_option), build_fn(error = "crate::Error"))]
 pub struct Socket {
     /// What type of socket to create.
     #[serde(rename = "SockType")]
@@ -121,12 +121,14 @@ pub struct Socket {
 
     /// The mode of the socket.
     ///
-    /// <div class="warning">
-    ///     Known bug: Property lists don't support octal, so please convert
-    ///     the value to decimal.
-    /// </div>
+    /// Property lists don't support octal, so the mode is written as its
+    /// decimal equivalent.
+    ///
+    /// The builder also accepts bits such as `0o600` or a string such as
+    /// `"0600"` through `try_path_mode`.
     #[serde(rename = "SockPathMode")]
-    pub path_mode: Option<f32>,
+    #[builder(try_setter)]
+    pub path_mode: Option<FileMode>,
 
     /// Request that the service be registered with the the Bonjour subsystem.
     ///
@@ -145,7 +147,7 @@ pub struct Socket {
 }
 
 /// The type of socket to create.
-#[derive(Clone, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum SocketType {
     Stream,
     Dgram,
@@ -153,7 +155,7 @@ pub enum SocketType {
 }
 
 /// The family of socket to create.
-#[derive(Clone, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum SocketFamily {
     IPv4,
     IPv6,
@@ -162,13 +164,13 @@ pub enum SocketFamily {
 }
 
 /// The protocol to use for the socket.
-#[derive(Clone, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum SocketProtocol {
     TCP,
     UDP,
 }
 
-#[derive(Clone, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(untagged)]
 pub enum Bonjour {
     Bool(bool),
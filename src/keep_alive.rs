@@ -1,7 +1,21 @@
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{self, IgnoredAny, MapAccess, Visitor},
+};
+use std::{collections::BTreeMap, fmt};
 
-#[derive(Clone, Deserialize, Serialize)]
+mod decision;
+mod respawn;
+
+#[cfg(test)]
+mod tests;
+
+pub use decision::{Condition, Decision, ExitStatus, JobState};
+pub use respawn::{
+    DEFAULT_THROTTLE_INTERVAL, Launch, LaunchReason, Lifetime, RespawnSimulation, StopReason,
+};
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
 #[serde(untagged)]
 pub enum KeepAlive {
     Bool(bool),
@@ -36,7 +50,7 @@ pub enum KeepAlive {
         ///     race-prone and lossy. This option should be avoided in favor of
         ///     demand-based alternatives using IPC.
         /// </div>
-        path_state: Option<HashMap<String, bool>>,
+        path_state: Option<BTreeMap<String, bool>>,
 
         /// Each key in this dictionary is the name of another job. If the
         /// value is `true`, then the job will be kept alive as long as one of
@@ -48,7 +62,7 @@ pub enum KeepAlive {
         ///     If multiple jobs need to coordinate coordinate their
         ///     lifecycles, they should establish contracts using IPC.
         /// </div>
-        other_job_enabled: Option<HashMap<String, bool>>,
+        other_job_enabled: Option<BTreeMap<String, bool>>,
 
         /// If `true`, the the job will be restarted as long as it exited due
         /// to a signal which is typically associated with a crash (`SIGILL`,
@@ -57,3 +71,57 @@ pub enum KeepAlive {
         crashed: Option<bool>,
     },
 }
+
+impl<'de> Deserialize<'de> for KeepAlive {
+    /// Deserializes either form by hand rather than as an untagged enum, so
+    /// that errors inside the dictionary keep their key path, such as
+    /// `KeepAlive.SuccessfulExit`.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeepAliveVisitor;
+
+        impl<'de> Visitor<'de> for KeepAliveVisitor {
+            type Value = KeepAlive;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a boolean or a dictionary of conditions")
+            }
+
+            fn visit_bool<E: de::Error>(self, value: bool) -> Result<Self::Value, E> {
+                Ok(KeepAlive::Bool(value))
+            }
+
+            #[allow(deprecated)]
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut successful_exit = None;
+                let mut network_state = None;
+                let mut path_state = None;
+                let mut other_job_enabled = None;
+                let mut crashed = None;
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "SuccessfulExit" => successful_exit = Some(map.next_value()?),
+                        "NetworkState" => network_state = Some(map.next_value()?),
+                        "PathState" => path_state = Some(map.next_value()?),
+                        "OtherJobEnabled" => other_job_enabled = Some(map.next_value()?),
+                        "Crashed" => crashed = Some(map.next_value()?),
+                        // Unknown conditions are kept in `LaunchAgent::extra`.
+                        _ => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+
+                Ok(KeepAlive::Object {
+                    successful_exit,
+                    network_state,
+                    path_state,
+                    other_job_enabled,
+                    crashed,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(KeepAliveVisitor)
+    }
+}
@@ -0,0 +1,236 @@
+use std::{collections::BTreeSet, fmt};
+
+use super::KeepAlive;
+
+/// Signals that `launchd` considers a crash: `SIGILL`, `SIGTRAP`, `SIGABRT`,
+/// `SIGEMT`, `SIGFPE`, `SIGBUS`, `SIGSEGV` and `SIGSYS`.
+const CRASH_SIGNALS: [i32; 8] = [4, 5, 6, 7, 8, 10, 11, 12];
+
+/// How a job's process last terminated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// The process called `exit(3)` with the given status.
+    Exited(i32),
+
+    /// The process was terminated by the given signal.
+    Signaled(i32),
+}
+
+impl ExitStatus {
+    /// Whether the process exited with a status of zero.
+    pub fn is_success(&self) -> bool {
+        *self == Self::Exited(0)
+    }
+
+    /// Whether the process was terminated by a signal typically associated
+    /// with a crash, such as `SIGSEGV`.
+    pub fn is_crash(&self) -> bool {
+        matches!(self, Self::Signaled(signal) if CRASH_SIGNALS.contains(signal))
+    }
+}
+
+impl fmt::Display for ExitStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exited(status) => write!(f, "exited with status {status}"),
+            Self::Signaled(signal) => write!(f, "terminated by signal {signal}"),
+        }
+    }
+}
+
+/// A snapshot of everything [`KeepAlive::should_keep_alive`] depends on.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JobState {
+    /// How the job last terminated, or `None` if it has not run since it was
+    /// loaded.
+    pub last_exit: Option<ExitStatus>,
+
+    /// The file-system paths that currently exist.
+    pub existing_paths: BTreeSet<String>,
+
+    /// The labels of the other jobs currently loaded in `launchd`.
+    pub loaded_jobs: BTreeSet<String>,
+}
+
+impl JobState {
+    /// A job that has not run since it was loaded.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records how the job last terminated.
+    pub fn with_exit(mut self, status: ExitStatus) -> Self {
+        self.last_exit = Some(status);
+        self
+    }
+
+    /// Records that `path` exists.
+    pub fn with_path(mut self, path: &str) -> Self {
+        self.existing_paths.insert(path.to_string());
+        self
+    }
+
+    /// Records that the job labeled `label` is loaded.
+    pub fn with_loaded_job(mut self, label: &str) -> Self {
+        self.loaded_jobs.insert(label.to_string());
+        self
+    }
+}
+
+/// A reason `launchd` has to keep a job alive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Condition {
+    /// [`KeepAlive`] is `true`.
+    Always,
+
+    /// `SuccessfulExit` is set but the job has not run yet, which implies
+    /// [`run_at_load`](crate::LaunchAgent::run_at_load).
+    ImpliedRunAtLoad,
+
+    /// The last exit matched `SuccessfulExit`.
+    SuccessfulExit(bool),
+
+    /// The existence of `path` matched its `PathState` entry.
+    PathState { path: String, exists: bool },
+
+    /// Whether `label` is loaded matched its `OtherJobEnabled` entry.
+    OtherJobEnabled { label: String, loaded: bool },
+
+    /// Whether the job crashed matched `Crashed`.
+    Crashed(bool),
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Always => write!(f, "KeepAlive is true"),
+            Self::ImpliedRunAtLoad => {
+                write!(
+                    f,
+                    "SuccessfulExit implies RunAtLoad and the job has not run"
+                )
+            }
+            Self::SuccessfulExit(true) => write!(f, "the job exited successfully"),
+            Self::SuccessfulExit(false) => write!(f, "the job did not exit successfully"),
+            Self::PathState { path, exists: true } => write!(f, "{path} exists"),
+            Self::PathState {
+                path,
+                exists: false,
+            } => write!(f, "{path} does not exist"),
+            Self::OtherJobEnabled {
+                label,
+                loaded: true,
+            } => write!(f, "{label} is loaded"),
+            Self::OtherJobEnabled {
+                label,
+                loaded: false,
+            } => write!(f, "{label} is not loaded"),
+            Self::Crashed(true) => write!(f, "the job crashed"),
+            Self::Crashed(false) => write!(f, "the job did not crash"),
+        }
+    }
+}
+
+/// Whether `launchd` would keep a job alive, and why.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Decision {
+    /// The job would be (re)started because of every listed condition.
+    KeepAlive(Vec<Condition>),
+
+    /// No condition matched, so the job is left to demand-based invocation.
+    OnDemand,
+}
+
+impl Decision {
+    pub fn should_keep_alive(&self) -> bool {
+        matches!(self, Self::KeepAlive(_))
+    }
+
+    /// The conditions that matched.
+    pub fn conditions(&self) -> &[Condition] {
+        match self {
+            Self::KeepAlive(conditions) => conditions,
+            Self::OnDemand => &[],
+        }
+    }
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KeepAlive(conditions) => {
+                write!(f, "kept alive because ")?;
+                for (index, condition) in conditions.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " or ")?;
+                    }
+                    write!(f, "{condition}")?;
+                }
+                Ok(())
+            }
+            Self::OnDemand => write!(f, "no KeepAlive condition matched; launched on demand"),
+        }
+    }
+}
+
+impl KeepAlive {
+    /// Decides whether `launchd` would keep the job alive given `state`.
+    ///
+    /// Conditions are ORed together, as `launchd` does: every condition that
+    /// holds is reported, and any one of them is enough to keep the job
+    /// alive. `SuccessfulExit` and `Crashed` only apply once the job has
+    /// exited, and the deprecated `NetworkState` is ignored.
+    pub fn should_keep_alive(&self, state: &JobState) -> Decision {
+        let mut conditions = Vec::new();
+
+        match self {
+            KeepAlive::Bool(true) => conditions.push(Condition::Always),
+            KeepAlive::Bool(false) => {}
+            KeepAlive::Object {
+                successful_exit,
+                path_state,
+                other_job_enabled,
+                crashed,
+                ..
+            } => {
+                match (successful_exit, state.last_exit) {
+                    (Some(_), None) => conditions.push(Condition::ImpliedRunAtLoad),
+                    (Some(expected), Some(status)) if status.is_success() == *expected => {
+                        conditions.push(Condition::SuccessfulExit(*expected))
+                    }
+                    _ => {}
+                }
+
+                for (path, exists) in path_state.iter().flatten() {
+                    if state.existing_paths.contains(path) == *exists {
+                        conditions.push(Condition::PathState {
+                            path: path.clone(),
+                            exists: *exists,
+                        });
+                    }
+                }
+
+                for (label, loaded) in other_job_enabled.iter().flatten() {
+                    if state.loaded_jobs.contains(label) == *loaded {
+                        conditions.push(Condition::OtherJobEnabled {
+                            label: label.clone(),
+                            loaded: *loaded,
+                        });
+                    }
+                }
+
+                if let (Some(expected), Some(status)) = (crashed, state.last_exit)
+                    && status.is_crash() == *expected
+                {
+                    conditions.push(Condition::Crashed(*expected));
+                }
+            }
+        }
+
+        if conditions.is_empty() {
+            Decision::OnDemand
+        } else {
+            Decision::KeepAlive(conditions)
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use std::{fmt, time::Duration};
+
+use crate::LaunchAgent;
+
+use super::{Decision, ExitStatus, JobState};
+
+/// How long `launchd` waits between spawns of a job when
+/// [`throttle_interval`](crate::LaunchAgent::throttle_interval) is not set.
+pub const DEFAULT_THROTTLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// One scripted run of a job's process: how long it stays up and how it ends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lifetime {
+    pub runs_for: Duration,
+    pub exit: ExitStatus,
+}
+
+impl Lifetime {
+    pub fn new(runs_for: Duration, exit: ExitStatus) -> Self {
+        Self { runs_for, exit }
+    }
+}
+
+/// Why `launchd` started the job.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LaunchReason {
+    /// [`run_at_load`](crate::LaunchAgent::run_at_load) is `true`.
+    RunAtLoad,
+
+    /// A `KeepAlive` condition held.
+    KeepAlive(Decision),
+
+    /// Nothing would start the job at load, so the first run is assumed to
+    /// have been requested on demand, such as by a socket or a trigger.
+    Demand,
+}
+
+impl fmt::Display for LaunchReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RunAtLoad => write!(f, "RunAtLoad is true"),
+            Self::KeepAlive(decision) => write!(f, "{decision}"),
+            Self::Demand => write!(f, "on demand"),
+        }
+    }
+}
+
+/// A single start of the job in a [`RespawnSimulation`]. Times are measured
+/// from when the job was loaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Launch {
+    pub at: Duration,
+    pub reason: LaunchReason,
+    pub ran_for: Duration,
+    pub exit: ExitStatus,
+
+    /// How long the job was down between the previous exit and this launch
+    /// because of throttling, or zero for the first launch.
+    pub downtime: Duration,
+}
+
+impl Launch {
+    pub fn exited_at(&self) -> Duration {
+        self.at + self.ran_for
+    }
+}
+
+/// Why a [`RespawnSimulation`] stopped launching the job.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    /// [`launch_only_once`](crate::LaunchAgent::launch_only_once) is `true`,
+    /// so the job is never respawned.
+    LaunchOnlyOnce,
+
+    /// No `KeepAlive` condition held after the last exit, or nothing starts
+    /// the job at load and the script is empty, so the job waits to be
+    /// launched on demand.
+    OnDemand,
+
+    /// The job would still be launched or respawned at `next_launch`, but the
+    /// script has no more lifetimes to run.
+    ScriptEnded { next_launch: Duration },
+}
+
+/// The launches `launchd` would make for a job given a scripted sequence of
+/// process lifetimes, built by
+/// [`LaunchAgent::simulate_respawns`](crate::LaunchAgent::simulate_respawns).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RespawnSimulation {
+    throttle_interval: Duration,
+    launches: Vec<Launch>,
+    stop: StopReason,
+}
+
+impl RespawnSimulation {
+    /// The throttle interval that was applied between spawns.
+    pub fn throttle_interval(&self) -> Duration {
+        self.throttle_interval
+    }
+
+    pub fn launches(&self) -> &[Launch] {
+        &self.launches
+    }
+
+    pub fn stop_reason(&self) -> StopReason {
+        self.stop
+    }
+
+    /// The total time the job spent waiting out its throttle interval.
+    pub fn total_downtime(&self) -> Duration {
+        self.launches.iter().map(|launch| launch.downtime).sum()
+    }
+}
+
+impl fmt::Display for RespawnSimulation {
+    /// Writes the simulation as a timeline, one event per line, explaining
+    /// every launch and every second of downtime.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let throttle = seconds(self.throttle_interval);
+        let mut previous: Option<&Launch> = None;
+
+        for launch in &self.launches {
+            match previous {
+                None => writeln!(f, "+{}: launched ({})", seconds(launch.at), launch.reason)?,
+                Some(previous) if launch.downtime > Duration::ZERO => writeln!(
+                    f,
+                    "+{}: respawned after {} of throttling, as the previous run lasted {}, less than the {throttle} ThrottleInterval ({})",
+                    seconds(launch.at),
+                    seconds(launch.downtime),
+                    seconds(previous.ran_for),
+                    launch.reason
+                )?,
+                Some(_) => writeln!(
+                    f,
+                    "+{}: respawned immediately ({})",
+                    seconds(launch.at),
+                    launch.reason
+                )?,
+            }
+            writeln!(
+                f,
+                "+{}: {} after {}",
+                seconds(launch.exited_at()),
+                launch.exit,
+                seconds(launch.ran_for)
+            )?;
+            previous = Some(launch);
+        }
+
+        match self.stop {
+            StopReason::LaunchOnlyOnce => {
+                write!(f, "not respawned: LaunchOnlyOnce is true")
+            }
+            StopReason::OnDemand if self.launches.is_empty() => write!(
+                f,
+                "not launched: nothing starts the job at load; waiting to be launched on demand"
+            ),
+            StopReason::OnDemand => write!(
+                f,
+                "not respawned: no KeepAlive condition matched; waiting to be launched on demand"
+            ),
+            StopReason::ScriptEnded { next_launch } if self.launches.is_empty() => write!(
+                f,
+                "+{}: would launch at load, but the script has no lifetimes",
+                seconds(next_launch)
+            ),
+            StopReason::ScriptEnded { next_launch } => write!(
+                f,
+                "+{}: would launch again, but the script has no more lifetimes",
+                seconds(next_launch)
+            ),
+        }
+    }
+}
+
+impl LaunchAgent {
+    /// Simulates how `launchd` would respawn the job if its process ran for
+    /// each of `lifetimes` in turn, with `environment` supplying the paths and
+    /// other jobs that `KeepAlive` conditions check.
+    ///
+    /// The first lifetime starts when the job is loaded. After each exit the
+    /// job is respawned only if a `KeepAlive` condition holds and
+    /// [`launch_only_once`](Self::launch_only_once) is not set. `launchd` will
+    /// not spawn a job more often than once per
+    /// [`throttle_interval`](Self::throttle_interval), or
+    /// [`DEFAULT_THROTTLE_INTERVAL`] when it is not set, so a process that
+    /// exits sooner than that stays down for the remainder of the interval.
+    pub fn simulate_respawns(
+        &self,
+        lifetimes: impl IntoIterator<Item = Lifetime>,
+        environment: &JobState,
+    ) -> RespawnSimulation {
+        let throttle_interval = self
+            .throttle_interval_duration()
+            .unwrap_or(DEFAULT_THROTTLE_INTERVAL);
+        let decide = |last_exit: Option<ExitStatus>| {
+            let state = JobState {
+                last_exit,
+                ..environment.clone()
+            };
+            self.keep_alive
+                .as_ref()
+                .map_or(Decision::OnDemand, |keep_alive| {
+                    keep_alive.should_keep_alive(&state)
+                })
+        };
+
+        let mut reason = match decide(None) {
+            _ if self.run_at_load == Some(true) => LaunchReason::RunAtLoad,
+            decision @ Decision::KeepAlive(_) => LaunchReason::KeepAlive(decision),
+            Decision::OnDemand => LaunchReason::Demand,
+        };
+        let mut at = Duration::ZERO;
+        let mut downtime = Duration::ZERO;
+        let mut launches = Vec::new();
+
+        for lifetime in lifetimes {
+            let launch = Launch {
+                at,
+                reason,
+                ran_for: lifetime.runs_for,
+                exit: lifetime.exit,
+                downtime,
+            };
+            let exited_at = launch.exited_at();
+            launches.push(launch);
+
+            if self.launch_only_once == Some(true) {
+                return RespawnSimulation {
+                    throttle_interval,
+                    launches,
+                    stop: StopReason::LaunchOnlyOnce,
+                };
+            }
+
+            let decision = decide(Some(lifetime.exit));
+            if !decision.should_keep_alive() {
+                return RespawnSimulation {
+                    throttle_interval,
+                    launches,
+                    stop: StopReason::OnDemand,
+                };
+            }
+
+            reason = LaunchReason::KeepAlive(decision);
+            at = exited_at.max(at + throttle_interval);
+            downtime = at - exited_at;
+        }
+
+        // With an empty script, only a job started at load would launch.
+        let stop = match reason {
+            LaunchReason::Demand if launches.is_empty() => StopReason::OnDemand,
+            _ => StopReason::ScriptEnded { next_launch: at },
+        };
+
+        RespawnSimulation {
+            throttle_interval,
+            launches,
+            stop,
+        }
+    }
+}
+
+/// Formats a duration as seconds, such as `10s` or `2.5s`.
+fn seconds(duration: Duration) -> String {
+    format!("{}s", duration.as_secs_f64())
+}
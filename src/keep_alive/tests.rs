@@ -0,0 +1,290 @@
+#![allow(deprecated)]
+
+use super::*;
+use std::time::Duration;
+
+fn conditions(
+    successful_exit: Option<bool>,
+    path_state: &[(&str, bool)],
+    other_job_enabled: &[(&str, bool)],
+    crashed: Option<bool>,
+) -> KeepAlive {
+    let map = |entries: &[(&str, bool)]| {
+        (!entries.is_empty()).then(|| {
+            entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), *value))
+                .collect()
+        })
+    };
+
+    KeepAlive::Object {
+        successful_exit,
+        network_state: None,
+        path_state: map(path_state),
+        other_job_enabled: map(other_job_enabled),
+        crashed,
+    }
+}
+
+#[test]
+fn bool_keep_alive_is_unconditional() {
+    let state = JobState::new().with_exit(ExitStatus::Exited(0));
+
+    assert_eq!(
+        KeepAlive::Bool(true).should_keep_alive(&state),
+        Decision::KeepAlive(vec![Condition::Always])
+    );
+    assert_eq!(
+        KeepAlive::Bool(false).should_keep_alive(&state),
+        Decision::OnDemand
+    );
+}
+
+#[test]
+fn successful_exit_implies_run_at_load_and_checks_the_exit_status() {
+    let keep_alive = conditions(Some(false), &[], &[], None);
+
+    assert_eq!(
+        keep_alive.should_keep_alive(&JobState::new()),
+        Decision::KeepAlive(vec![Condition::ImpliedRunAtLoad])
+    );
+    assert!(
+        !keep_alive
+            .should_keep_alive(&JobState::new().with_exit(ExitStatus::Exited(0)))
+            .should_keep_alive()
+    );
+    assert_eq!(
+        keep_alive.should_keep_alive(&JobState::new().with_exit(ExitStatus::Exited(1))),
+        Decision::KeepAlive(vec![Condition::SuccessfulExit(false)])
+    );
+}
+
+#[test]
+fn crashed_only_matches_crash_signals() {
+    let keep_alive = conditions(None, &[], &[], Some(true));
+
+    assert!(
+        keep_alive
+            .should_keep_alive(&JobState::new().with_exit(ExitStatus::Signaled(11)))
+            .should_keep_alive()
+    );
+    assert!(
+        !keep_alive
+            .should_keep_alive(&JobState::new().with_exit(ExitStatus::Signaled(15)))
+            .should_keep_alive()
+    );
+    assert!(
+        !keep_alive
+            .should_keep_alive(&JobState::new())
+            .should_keep_alive()
+    );
+}
+
+#[test]
+fn conditions_are_ored_and_every_match_is_explained() {
+    let keep_alive = conditions(
+        Some(true),
+        &[("/tmp/run", true), ("/tmp/stop", false)],
+        &[("com.example.other", true)],
+        None,
+    );
+    let state = JobState::new()
+        .with_exit(ExitStatus::Exited(0))
+        .with_loaded_job("com.example.other");
+
+    let decision = keep_alive.should_keep_alive(&state);
+
+    assert_eq!(
+        decision.conditions(),
+        [
+            Condition::SuccessfulExit(true),
+            Condition::PathState {
+                path: "/tmp/stop".to_string(),
+                exists: false
+            },
+            Condition::OtherJobEnabled {
+                label: "com.example.other".to_string(),
+                loaded: true
+            },
+        ]
+    );
+    assert_eq!(
+        decision.to_string(),
+        "kept alive because the job exited successfully or /tmp/stop does not exist or com.example.other is loaded"
+    );
+}
+
+fn agent(configure: impl FnOnce(&mut crate::LaunchAgentBuilder)) -> crate::LaunchAgent {
+    let mut builder = crate::LaunchAgentBuilder::default();
+    builder
+        .label("com.example.test")
+        .program("/usr/bin/example");
+    configure(&mut builder);
+    builder.build().unwrap()
+}
+
+fn lifetime(seconds: u64, exit: ExitStatus) -> Lifetime {
+    Lifetime::new(Duration::from_secs(seconds), exit)
+}
+
+#[test]
+fn crash_loops_are_throttled_to_the_default_interval() {
+    let agent = agent(|builder| {
+        builder.keep_alive(KeepAlive::Bool(true));
+    });
+
+    let simulation = agent.simulate_respawns(
+        [
+            lifetime(2, ExitStatus::Signaled(11)),
+            lifetime(30, ExitStatus::Exited(1)),
+            lifetime(1, ExitStatus::Exited(1)),
+        ],
+        &JobState::new(),
+    );
+
+    let starts: Vec<_> = simulation
+        .launches()
+        .iter()
+        .map(|launch| (launch.at.as_secs(), launch.downtime.as_secs()))
+        .collect();
+    assert_eq!(starts, [(0, 0), (10, 8), (40, 0)]);
+    assert_eq!(
+        simulation.stop_reason(),
+        StopReason::ScriptEnded {
+            next_launch: Duration::from_secs(50)
+        }
+    );
+    assert_eq!(simulation.total_downtime(), Duration::from_secs(8));
+    assert_eq!(
+        simulation.to_string(),
+        "\
++0s: launched (kept alive because KeepAlive is true)
++2s: terminated by signal 11 after 2s
++10s: respawned after 8s of throttling, as the previous run lasted 2s, less than the 10s ThrottleInterval (kept alive because KeepAlive is true)
++40s: exited with status 1 after 30s
++40s: respawned immediately (kept alive because KeepAlive is true)
++41s: exited with status 1 after 1s
++50s: would launch again, but the script has no more lifetimes"
+    );
+}
+
+#[test]
+fn throttle_interval_overrides_the_default() {
+    let agent = agent(|builder| {
+        builder
+            .keep_alive(KeepAlive::Bool(true))
+            .try_throttle_interval("1m")
+            .unwrap();
+    });
+
+    let simulation = agent.simulate_respawns(
+        [
+            lifetime(5, ExitStatus::Exited(1)),
+            lifetime(5, ExitStatus::Exited(1)),
+        ],
+        &JobState::new(),
+    );
+
+    assert_eq!(simulation.throttle_interval(), Duration::from_secs(60));
+    assert_eq!(simulation.launches()[1].at, Duration::from_secs(60));
+    assert_eq!(simulation.total_downtime(), Duration::from_secs(55));
+}
+
+#[test]
+fn respawns_stop_when_no_keep_alive_condition_holds() {
+    let agent = agent(|builder| {
+        builder.keep_alive(conditions(Some(false), &[], &[], None));
+    });
+
+    let simulation = agent.simulate_respawns(
+        [
+            lifetime(3, ExitStatus::Exited(1)),
+            lifetime(20, ExitStatus::Exited(0)),
+            lifetime(20, ExitStatus::Exited(1)),
+        ],
+        &JobState::new(),
+    );
+
+    assert_eq!(simulation.launches().len(), 2);
+    assert_eq!(
+        simulation.launches()[0].reason,
+        LaunchReason::KeepAlive(Decision::KeepAlive(vec![Condition::ImpliedRunAtLoad]))
+    );
+    assert_eq!(
+        simulation.launches()[1].reason,
+        LaunchReason::KeepAlive(Decision::KeepAlive(vec![Condition::SuccessfulExit(false)]))
+    );
+    assert_eq!(simulation.stop_reason(), StopReason::OnDemand);
+}
+
+#[test]
+fn path_state_is_checked_against_the_environment() {
+    let agent = agent(|builder| {
+        builder.keep_alive(conditions(None, &[("/tmp/run", true)], &[], None));
+    });
+    let lifetimes = [lifetime(60, ExitStatus::Exited(0))];
+
+    let present = agent.simulate_respawns(lifetimes, &JobState::new().with_path("/tmp/run"));
+    let absent = agent.simulate_respawns(lifetimes, &JobState::new());
+
+    assert!(matches!(
+        present.stop_reason(),
+        StopReason::ScriptEnded { .. }
+    ));
+    assert_eq!(absent.launches()[0].reason, LaunchReason::Demand);
+    assert_eq!(absent.stop_reason(), StopReason::OnDemand);
+}
+
+#[test]
+fn empty_scripts_only_launch_jobs_started_at_load() {
+    let idle = agent(|_| {}).simulate_respawns([], &JobState::new());
+    let kept_alive = agent(|builder| {
+        builder.keep_alive(KeepAlive::Bool(true));
+    })
+    .simulate_respawns([], &JobState::new());
+
+    assert_eq!(idle.launches(), []);
+    assert_eq!(idle.stop_reason(), StopReason::OnDemand);
+    assert_eq!(
+        idle.to_string(),
+        "not launched: nothing starts the job at load; waiting to be launched on demand"
+    );
+    assert_eq!(
+        kept_alive.stop_reason(),
+        StopReason::ScriptEnded {
+            next_launch: Duration::ZERO
+        }
+    );
+    assert_eq!(
+        kept_alive.to_string(),
+        "+0s: would launch at load, but the script has no lifetimes"
+    );
+}
+
+#[test]
+fn launch_only_once_never_respawns() {
+    let agent = agent(|builder| {
+        builder
+            .keep_alive(KeepAlive::Bool(true))
+            .run_at_load(true)
+            .launch_only_once(true);
+    });
+
+    let simulation = agent.simulate_respawns(
+        [
+            lifetime(1, ExitStatus::Signaled(6)),
+            lifetime(1, ExitStatus::Signaled(6)),
+        ],
+        &JobState::new(),
+    );
+
+    assert_eq!(simulation.launches().len(), 1);
+    assert_eq!(simulation.launches()[0].reason, LaunchReason::RunAtLoad);
+    assert_eq!(simulation.stop_reason(), StopReason::LaunchOnlyOnce);
+    assert!(
+        simulation
+            .to_string()
+            .ends_with("not respawned: LaunchOnlyOnce is true")
+    );
+}
@@ -1,3 +1,4 @@
+mod extra;
 mod impls;
 mod structs;
 
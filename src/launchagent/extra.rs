@@ -0,0 +1,115 @@
+use plist::{Dictionary, Value};
+
+use crate::error::{Error, Result};
+
+/// The parts of `original` that are missing from `modeled`, the same
+/// property list after a trip through [`LaunchAgent`](super::LaunchAgent).
+///
+/// Unknown keys are kept at the same key path, so a dictionary in the result
+/// either is an unknown value or holds the unknown keys of the dictionary
+/// with the same key in `modeled`. In arrays, elements without unknown keys
+/// are left as empty dictionaries to keep the other elements in place.
+pub(super) fn unknown(original: &Value, modeled: &Value) -> Option<Value> {
+    match (original, modeled) {
+        (Value::Dictionary(original), Value::Dictionary(modeled)) => {
+            let unknown: Dictionary = original
+                .iter()
+                .filter_map(|(key, value)| match modeled.get(key) {
+                    None => Some((key.clone(), value.clone())),
+                    Some(modeled) => Some((key.clone(), unknown(value, modeled)?)),
+                })
+                .collect();
+            (!unknown.is_empty()).then_some(Value::Dictionary(unknown))
+        }
+        (Value::Array(original), Value::Array(modeled)) if original.len() == modeled.len() => {
+            let unknown: Vec<Option<Value>> = original
+                .iter()
+                .zip(modeled)
+                .map(|(original, modeled)| unknown(original, modeled))
+                .collect();
+            unknown.iter().any(Option::is_some).then(|| {
+                Value::Array(
+                    unknown
+                        .into_iter()
+                        .map(|value| value.unwrap_or_else(|| Dictionary::new().into()))
+                        .collect(),
+                )
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Merges the unknown keys in `extra` back into `fields`, failing if one of
+/// them is also set on a field.
+pub(super) fn merge(fields: &mut Dictionary, extra: &Dictionary, key_path: &str) -> Result<()> {
+    for (key, value) in extra {
+        let key_path = join(key_path, key);
+        match (fields.get_mut(key), value) {
+            (None, value) => {
+                fields.insert(key.clone(), value.clone());
+            }
+            (Some(Value::Dictionary(fields)), Value::Dictionary(extra)) => {
+                merge(fields, extra, &key_path)?;
+            }
+            (Some(Value::Array(fields)), Value::Array(extra)) if fields.len() == extra.len() => {
+                for (index, (fields, extra)) in fields.iter_mut().zip(extra).enumerate() {
+                    let Value::Dictionary(extra) = extra else {
+                        return Err(conflict(format!("{key_path}[{index}]")));
+                    };
+                    match fields {
+                        _ if extra.is_empty() => {}
+                        Value::Dictionary(fields) => {
+                            merge(fields, extra, &format!("{key_path}[{index}]"))?;
+                        }
+                        _ => return Err(conflict(format!("{key_path}[{index}]"))),
+                    }
+                }
+            }
+            (Some(_), _) => return Err(conflict(key_path)),
+        }
+    }
+
+    Ok(())
+}
+
+/// The key path of every unknown key in `extra`, given the dictionary the
+/// modeled fields serialize to.
+pub(super) fn key_paths(extra: &Dictionary, fields: &Dictionary, key_path: &str) -> Vec<String> {
+    let mut key_paths = Vec::new();
+
+    for (key, value) in extra {
+        let key_path = join(key_path, key);
+        match (fields.get(key), value) {
+            (Some(Value::Dictionary(fields)), Value::Dictionary(extra)) => {
+                key_paths.extend(self::key_paths(extra, fields, &key_path));
+            }
+            (Some(Value::Array(fields)), Value::Array(extra)) => {
+                for (index, (fields, extra)) in fields.iter().zip(extra).enumerate() {
+                    if let (Value::Dictionary(fields), Value::Dictionary(extra)) = (fields, extra) {
+                        let key_path = format!("{key_path}[{index}]");
+                        key_paths.extend(self::key_paths(extra, fields, &key_path));
+                    }
+                }
+            }
+            _ => key_paths.push(key_path),
+        }
+    }
+
+    key_paths
+}
+
+fn join(key_path: &str, key: &str) -> String {
+    if key_path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{key_path}.{key}")
+    }
+}
+
+fn conflict(key_path: String) -> Error {
+    Error::Conflict {
+        key_path,
+        message: "the key is set both on a field and in `extra`".to_string(),
+    }
+}
@@ -1,21 +1,43 @@
-use anyhow::{Context, Result};
 use std::{
     fs,
+    io::{BufWriter, Cursor, Read, Write},
     path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
 };
 
-use super::structs::{LaunchAgent, LaunchAgentBuilder};
+use crate::{
+    error::{Error, Result},
+    format::Format,
+    validation::Executable,
+};
+
+use super::{
+    extra,
+    structs::{LaunchAgent, LaunchAgentBuilder},
+};
 
 impl LaunchAgent {
     pub fn new(label: &str, program: &str) -> Self {
+        Self::try_new(label, program).unwrap()
+    }
+
+    pub fn new_with_args(label: &str, program_arguments: Vec<&str>) -> Self {
+        Self::try_new_with_args(label, program_arguments).unwrap()
+    }
+
+    /// Creates a `LaunchAgent` that runs `program`, failing if the result
+    /// would not pass [`validate`](Self::validate).
+    pub fn try_new(label: &str, program: &str) -> Result<Self> {
         LaunchAgentBuilder::default()
             .label(label)
             .program(program)
             .build()
-            .unwrap()
     }
 
-    pub fn new_with_args(label: &str, program_arguments: Vec<&str>) -> Self {
+    /// Creates a `LaunchAgent` that runs `program_arguments`, failing if the
+    /// result would not pass [`validate`](Self::validate).
+    pub fn try_new_with_args(label: &str, program_arguments: Vec<&str>) -> Result<Self> {
         let program_arguments: Vec<String> =
             program_arguments.into_iter().map(String::from).collect();
 
@@ -23,18 +45,223 @@ impl LaunchAgent {
             .label(label)
             .program_arguments(program_arguments)
             .build()
-            .unwrap()
     }
 
+    /// Checks the rules that [`LaunchAgentBuilder::build`] enforces, which is
+    /// useful for agents that were loaded rather than built.
+    pub fn validate(&self) -> Result<()> {
+        Executable {
+            label: &self.label,
+            program: self.program.as_deref(),
+            program_arguments: self.program_arguments.as_deref(),
+            bundle_program: self.bundle_program.as_deref(),
+            nice: self.nice,
+        }
+        .validate()
+        .map_err(Error::from)
+    }
+
+    /// [`start_interval`](Self::start_interval) as a [`Duration`].
+    pub fn start_interval_duration(&self) -> Option<Duration> {
+        self.start_interval.map(Duration::from)
+    }
+
+    /// [`throttle_interval`](Self::throttle_interval) as a [`Duration`].
+    pub fn throttle_interval_duration(&self) -> Option<Duration> {
+        self.throttle_interval.map(Duration::from)
+    }
+
+    /// [`exit_time_out`](Self::exit_time_out) as a [`Duration`].
+    pub fn exit_time_out_duration(&self) -> Option<Duration> {
+        self.exit_time_out.map(Duration::from)
+    }
+
+    /// Loads a `LaunchAgent` from the property list at `path`.
+    ///
+    /// The encoding (XML, binary or OpenStep/ASCII) is detected automatically.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|error| Error::from(error).at_path(path))?;
+
+        Self::from_bytes(&bytes).map_err(|error| error.at_path(path))
+    }
+
+    /// Reads a `LaunchAgent` from a property list in any supported encoding.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        Self::from_bytes(&bytes)
+    }
+
+    /// Parses a `LaunchAgent` from the bytes of a property list in any
+    /// supported encoding.
+    ///
+    /// Keys that are not modeled by this crate, including those nested in
+    /// modeled keys, are kept in [`extra`](Self::extra).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let value =
+            plist::Value::from_reader(Cursor::new(bytes)).map_err(|source| Error::Deserialize {
+                path: None,
+                key_path: String::new(),
+                source,
+            })?;
+
+        // `plist` does not report where a value failed to deserialize, so the
+        // key path is tracked alongside it. This uses `plist`'s unstable
+        // streaming API, which is why the dependency is pinned exactly.
+        let mut deserializer = plist::Deserializer::new(value.events().map(Ok));
+        let mut agent: LaunchAgent =
+            serde_path_to_error::deserialize(&mut deserializer).map_err(|error| {
+                let key_path = error.path().to_string();
+                Error::Deserialize {
+                    path: None,
+                    key_path: if key_path == "." {
+                        String::new()
+                    } else {
+                        key_path
+                    },
+                    source: error.into_inner(),
+                }
+            })?;
+
+        let modeled = plist::to_value(&agent).map_err(Error::Serialize)?;
+        if let Some(plist::Value::Dictionary(unknown)) = extra::unknown(&value, &modeled) {
+            agent.extra = unknown;
+        }
+
+        Ok(agent)
+    }
+
+    /// Like [`load`](Self::load), but fails if the property list contains
+    /// keys that are not modeled by this crate, at any depth.
+    pub fn load_strict<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+
+        Self::load(path)?
+            .deny_unknown_keys()
+            .map_err(|error| error.at_path(path))
+    }
+
+    /// Like [`from_bytes`](Self::from_bytes), but fails if the property list
+    /// contains keys that are not modeled by this crate, at any depth.
+    pub fn from_bytes_strict(bytes: &[u8]) -> Result<Self> {
+        Self::from_bytes(bytes)?.deny_unknown_keys()
+    }
+
+    fn deny_unknown_keys(self) -> Result<Self> {
+        if self.extra.is_empty() {
+            return Ok(self);
+        }
+
+        let fields = plist::to_value(&self)
+            .map_err(Error::Serialize)?
+            .into_dictionary()
+            .expect("LaunchAgent serializes to a dictionary");
+
+        Err(Error::UnknownKeys {
+            path: None,
+            keys: extra::key_paths(&self.extra, &fields, ""),
+        })
+    }
+
+    /// Writes the `LaunchAgent` to `writer` as a property list in the given
+    /// format.
+    pub fn to_writer<W: Write>(&self, writer: W, format: Format) -> Result<()> {
+        let value = plist::Value::Dictionary(self.to_dictionary()?);
+
+        match format {
+            Format::Xml => value.to_writer_xml(writer),
+            Format::Binary => value.to_writer_binary(writer),
+        }
+        .map_err(|error| match error.into_io() {
+            Ok(source) => source.into(),
+            Err(error) => Error::Serialize(error),
+        })
+    }
+
+    /// Converts the `LaunchAgent` into a property list dictionary, including
+    /// the keys in [`extra`](Self::extra).
+    ///
+    /// Keys are written in a canonical order so that saving the same job
+    /// always produces the same bytes: `Label` first, then every other key
+    /// sorted alphabetically, as `plutil` does.
+    fn to_dictionary(&self) -> Result<plist::Dictionary> {
+        let mut dictionary = plist::to_value(self)
+            .map_err(Error::Serialize)?
+            .into_dictionary()
+            .expect("LaunchAgent serializes to a dictionary");
+
+        extra::merge(&mut dictionary, &self.extra, "")?;
+
+        sort_keys(&mut dictionary);
+        let (label, rest): (Vec<_>, Vec<_>) =
+            dictionary.into_iter().partition(|(key, _)| key == "Label");
+
+        Ok(label.into_iter().chain(rest).collect())
+    }
+
+    /// Serializes the `LaunchAgent` to an XML property list.
+    pub fn to_xml_string(&self) -> Result<String> {
+        let mut bytes = Vec::new();
+        self.to_writer(&mut bytes, Format::Xml)?;
+
+        Ok(String::from_utf8(bytes).expect("XML property lists are always UTF-8"))
+    }
+
+    /// Serializes the `LaunchAgent` to a binary property list.
+    pub fn to_binary_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.to_writer(&mut bytes, Format::Binary)?;
+
+        Ok(bytes)
+    }
+
+    /// Saves the `LaunchAgent` as `<out_dir>/<label>.plist` in the XML format.
     pub fn save<P: AsRef<Path>>(&self, out_dir: P) -> Result<()> {
         let path = PathBuf::from(out_dir.as_ref()).join(format!("{}.plist", self.label));
 
+        self.save_as(path, Format::Xml)
+    }
+
+    /// Saves the `LaunchAgent` to `path` in the given format, creating any
+    /// missing parent directories.
+    pub fn save_as<P: AsRef<Path>>(&self, path: P, format: Format) -> Result<()> {
+        let path = path.as_ref();
+
         if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)
-                .with_context(|| format!("Failed to create parent directories for {parent:?}"))?;
+            fs::create_dir_all(parent).map_err(|error| Error::from(error).at_path(parent))?;
         }
 
-        plist::to_file_xml(&path, &self)
-            .with_context(|| format!("Failed to save LaunchAgent to {path:?}"))
+        let file = fs::File::create(path).map_err(|error| Error::from(error).at_path(path))?;
+        let mut writer = BufWriter::new(file);
+
+        self.to_writer(&mut writer, format)
+            .and_then(|()| writer.flush().map_err(Error::from))
+            .map_err(|error| error.at_path(path))
+    }
+}
+
+/// Recursively sorts the keys of `dictionary` and of every dictionary nested
+/// within it.
+fn sort_keys(dictionary: &mut plist::Dictionary) {
+    fn sort_value(value: &mut plist::Value) {
+        match value {
+            plist::Value::Dictionary(dictionary) => sort_keys(dictionary),
+            plist::Value::Array(values) => values.iter_mut().for_each(sort_value),
+            _ => {}
+        }
+    }
+
+    dictionary.sort_keys();
+    dictionary.values_mut().for_each(sort_value);
+}
+
+impl FromStr for LaunchAgent {
+    type Err = Error;
+
+    /// Parses a `LaunchAgent` from an XML or OpenStep/ASCII property list.
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_bytes(s.as_bytes())
     }
 }
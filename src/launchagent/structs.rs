@@ -1,20 +1,29 @@
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use crate::{
-    constraints::{ProcessType, ResourceLimits, SessionType},
+    constraints::{ProcessType, ResourceLimits, SessionTypes},
+    error::Error,
+    events::LaunchEvents,
     ipc::{InetdCompatibility, MachService, SocketValue},
     keep_alive::KeepAlive,
+    modes::Umask,
     triggers::CalendarInterval,
-    unions::{StringOrF32, StringOrVec},
+    unions::StringOrVec,
+    units::Interval,
+    validation::{Executable, ValidationError},
 };
 
 /// Represents an XML property list that can be loaded into `launchd` with
 /// `launchctl`.
-#[derive(Builder, Default, Deserialize, Serialize)]
+#[derive(Builder, Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "PascalCase")]
-#[builder(default, setter(into, strip_option))]
+#[builder(
+    default,
+    setter(into, strip_option),
+    build_fn(validate = "Self::validate", error = "Error")
+)]
 pub struct LaunchAgent {
     /// Uniquely identifies the job to `launchd`.
     pub label: String,
@@ -57,7 +66,7 @@ pub struct LaunchAgent {
     ///
     /// Only applies to jobs which are agents. There are no
     /// distinct sessions in the privileged system context.
-    pub limit_load_to_session_type: Option<SessionType>,
+    pub limit_load_to_session_type: Option<SessionTypes>,
 
     /// This configuration file only applies to the hardware listed.
     ///
@@ -65,7 +74,7 @@ pub struct LaunchAgent {
     /// domain. Each value of the key defines valid values for the job to load.
     /// So a key of "model" with an array specifying only "MacBookPro4,2" would
     /// only load on a machine whose "hw.model" value was "MacBookPro4,2".
-    pub limit_load_to_hardware: Option<HashMap<String, Vec<String>>>,
+    pub limit_load_to_hardware: Option<BTreeMap<String, Vec<String>>>,
 
     /// This configuration file only applies to the hardware NOT listed.
     ///
@@ -74,7 +83,7 @@ pub struct LaunchAgent {
     /// not load. So a key of "model" with an array specifying only
     /// "MacBookPro4,2" would not load the job on a machine whose "hw.model"
     /// value was "MacBookPro4,2".
-    pub limit_load_from_hardware: Option<HashMap<String, Vec<String>>>,
+    pub limit_load_from_hardware: Option<BTreeMap<String, Vec<String>>>,
 
     /// Maps to the first argument of `execv(3)` and indicates the
     /// absolute path to the executable for the job.
@@ -214,7 +223,7 @@ pub struct LaunchAgent {
     /// the corresponding value being a string representing the desired value.
     ///
     /// NOTE: Values other than strings will be ignored.
-    pub environment_variables: Option<HashMap<String, String>>,
+    pub environment_variables: Option<BTreeMap<String, String>>,
 
     /// What value should be passed to `umask(2)` before running the job.
     ///
@@ -226,7 +235,14 @@ pub struct LaunchAgent {
     /// If a string that does not cleanly convert to an integer is specified,
     /// the behavior will be to set a `umask(2)` according to the `strtoul(3)`
     /// parsing rules.
-    pub umask: Option<StringOrF32>,
+    ///
+    /// Both forms are accepted when loading, and the value is always written
+    /// as an integer.
+    ///
+    /// The builder also accepts bits such as `0o022` or a string such as
+    /// `"022"` through `try_umask`.
+    #[builder(try_setter)]
+    pub umask: Option<Umask>,
 
     /// The recommended idle time out (in seconds) to pass to the job.
     ///
@@ -243,7 +259,11 @@ pub struct LaunchAgent {
     /// The default value is system-defined. The value zero is interpreted as
     /// infinity and should not be used, as it can stall system shutdown
     /// forever.
-    pub exit_time_out: Option<u32>,
+    ///
+    /// The builder also accepts a [`Duration`](std::time::Duration) or a
+    /// string such as `30s` through `try_exit_time_out`.
+    #[builder(try_setter)]
+    pub exit_time_out: Option<Interval>,
 
     /// Lets one override the default throttling policy imposed on jobs by
     /// `launchd`.
@@ -253,7 +273,11 @@ pub struct LaunchAgent {
     /// that jobs should linger around just in case they are needed again in
     /// the near future. This not only reduces the latency of responses, but it
     /// encourages developers to amortize the cost of program invocation.
-    pub throttle_interval: Option<u32>,
+    ///
+    /// The builder also accepts a [`Duration`](std::time::Duration) or a
+    /// string such as `1m` through `try_throttle_interval`.
+    #[builder(try_setter)]
+    pub throttle_interval: Option<Interval>,
 
     /// Whether `initgroups(3)` should initialize the group list for the job.
     ///
@@ -287,7 +311,11 @@ pub struct LaunchAgent {
     /// firing, that interval will be missed due to shortcomings in
     /// `kqueue(3)`. If the job is running during an interval firing, that
     /// interval firing will likewise be missed.
-    pub start_interval: Option<u32>,
+    ///
+    /// The builder also accepts a [`Duration`](std::time::Duration) or a
+    /// string such as `15m`, `2h30m` or `1d` through `try_start_interval`.
+    #[builder(try_setter)]
+    pub start_interval: Option<Interval>,
 
     /// Causes the job to be started every calendar interval as specified.
     ///
@@ -401,7 +429,7 @@ pub struct LaunchAgent {
     /// Each key in this dictionary should be the name of a service to be
     /// advertised. The value of the key must be a boolean and set to `true` or
     /// a dictionary in order for the service to be advertised.
-    pub mach_services: Option<HashMap<String, MachService>>,
+    pub mach_services: Option<BTreeMap<String, MachService>>,
 
     /// Launch-on-demand sockets that can be used to let `launchd` know when to
     /// run the job.
@@ -413,7 +441,7 @@ pub struct LaunchAgent {
     /// application level protocols (e.g. http vs. ftp vs. DNS...).
     ///
     /// The parameters are used as inputs to call `getaddrinfo(3)`.
-    pub sockets: Option<HashMap<String, SocketValue>>,
+    pub sockets: Option<BTreeMap<String, SocketValue>>,
 
     /// Higher-level event types to be used as launch-on-demand event sources.
     ///
@@ -424,7 +452,7 @@ pub struct LaunchAgent {
     /// specified to each event subsystem. With this key, the job promises to
     /// use the `xpc_set_event_stream_handler(3)` API to consume events. See
     /// `xpc_events(3)` for more details on event sources.
-    pub launch_events: Option<HashMap<String, HashMap<String, HashMap<String, String>>>>,
+    pub launch_events: Option<LaunchEvents>,
 
     #[deprecated(
         note = "This was a hack for jobs which could not properly keep track of their clients and is no longer implemented."
@@ -459,4 +487,34 @@ pub struct LaunchAgent {
     /// If an app installs a legacy plist the plist should include this with a
     /// value of the app's bundle identifier.
     pub associated_bundle_identifiers: Option<StringOrVec>,
+
+    /// Keys that are not modeled by this crate, such as Apple's private
+    /// `_`-prefixed keys or vendor-specific keys.
+    ///
+    /// These are filled in by [`from_bytes`](LaunchAgent::from_bytes) and written back
+    /// out by [`to_writer`](LaunchAgent::to_writer), so that loading and saving a job
+    /// is lossless. Unknown keys nested in a modeled key, such as
+    /// `KeepAlive.AfterInitialDemand`, are kept at the same key path: `extra`
+    /// then holds a `KeepAlive` dictionary with just those keys, which is
+    /// merged into the modeled one when writing.
+    ///
+    /// Serializing the `LaunchAgent` directly, as with `plist::to_writer_xml`,
+    /// leaves these keys out; use [`to_writer`](LaunchAgent::to_writer)
+    /// instead.
+    #[serde(skip)]
+    #[builder(setter(into = false, strip_option = false))]
+    pub extra: plist::Dictionary,
+}
+
+impl LaunchAgentBuilder {
+    fn validate(&self) -> Result<(), ValidationError> {
+        Executable {
+            label: self.label.as_deref().unwrap_or_default(),
+            program: self.program.as_ref().and_then(Option::as_deref),
+            program_arguments: self.program_arguments.as_ref().and_then(Option::as_deref),
+            bundle_program: self.bundle_program.as_ref().and_then(Option::as_deref),
+            nice: self.nice.flatten(),
+        }
+        .validate()
+    }
 }
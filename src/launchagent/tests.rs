@@ -1,4 +1,40 @@
 use super::*;
+use crate::{
+    Error, Format, Interval, ResourceLimitsBuilder, SessionType, SessionTypes, ValidationIssue,
+};
+use std::{
+    collections::BTreeMap,
+    io::Cursor,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// A directory under the system temporary directory that is unique to one
+/// test in one process, and is removed when dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "launchagent-{name}-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        Self(path)
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
 
 #[test]
 fn can_create_simple_launch_agent() {
@@ -22,3 +58,489 @@ fn can_create_simple_launch_agent_with_args() {
         vec!["/usr/bin/example", "--option", "value"]
     );
 }
+
+const XML_AGENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>com.example.test</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>/usr/bin/example</string>
+        <string>--option</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>StartInterval</key>
+    <integer>300</integer>
+</dict>
+</plist>
+"#;
+
+#[test]
+fn can_parse_xml_launch_agent() {
+    let agent: LaunchAgent = XML_AGENT.parse().unwrap();
+
+    assert_eq!(agent.label, "com.example.test");
+    assert_eq!(
+        agent.program_arguments.unwrap(),
+        vec!["/usr/bin/example", "--option"]
+    );
+    assert_eq!(agent.run_at_load, Some(true));
+    assert_eq!(agent.start_interval, Some(Interval::from(300)));
+}
+
+#[test]
+fn can_parse_ascii_launch_agent() {
+    let agent = LaunchAgent::from_bytes(
+        br#"{ Label = "com.example.test"; Program = "/usr/bin/example"; }"#,
+    )
+    .unwrap();
+
+    assert_eq!(agent.label, "com.example.test");
+    assert_eq!(agent.program.unwrap(), "/usr/bin/example");
+}
+
+#[test]
+fn can_read_binary_launch_agent() {
+    let agent = LaunchAgent::new("com.example.test", "/usr/bin/example");
+    let bytes = agent.to_binary_bytes().unwrap();
+
+    assert_eq!(LaunchAgent::from_reader(bytes.as_slice()).unwrap(), agent);
+}
+
+#[test]
+fn can_load_saved_launch_agent() {
+    let out_dir = TempDir::new("load-test");
+    let agent = LaunchAgent::new("com.example.load", "/usr/bin/example");
+    agent.save(out_dir.path()).unwrap();
+
+    let loaded = LaunchAgent::load(out_dir.path().join("com.example.load.plist")).unwrap();
+
+    assert_eq!(loaded, agent);
+}
+
+#[test]
+fn load_reports_the_path_on_error() {
+    let error = LaunchAgent::load("/nonexistent/com.example.missing.plist").unwrap_err();
+
+    assert!(error.to_string().contains("com.example.missing.plist"));
+}
+
+#[test]
+fn can_serialize_to_xml_string() {
+    let agent = LaunchAgent::new("com.example.test", "/usr/bin/example");
+    let xml = agent.to_xml_string().unwrap();
+
+    assert!(xml.contains("<key>Label</key>"));
+    assert_eq!(xml.parse::<LaunchAgent>().unwrap(), agent);
+}
+
+#[test]
+fn can_serialize_to_binary_bytes() {
+    let agent = LaunchAgent::new("com.example.test", "/usr/bin/example");
+    let bytes = agent.to_binary_bytes().unwrap();
+
+    assert!(bytes.starts_with(b"bplist00"));
+    assert_eq!(LaunchAgent::from_bytes(&bytes).unwrap(), agent);
+}
+
+#[test]
+fn can_save_as_binary_with_custom_name() {
+    let out_dir = TempDir::new("save-as-test");
+    let path = out_dir.path().join("custom.plist");
+    let agent = LaunchAgent::new("com.example.save_as", "/usr/bin/example");
+    agent.save_as(&path, Format::Binary).unwrap();
+
+    assert!(std::fs::read(&path).unwrap().starts_with(b"bplist00"));
+    assert_eq!(LaunchAgent::load(&path).unwrap(), agent);
+}
+
+#[test]
+fn build_rejects_agent_without_executable() {
+    let error = LaunchAgentBuilder::default()
+        .label("com.example.test")
+        .build()
+        .unwrap_err();
+
+    let Error::Validation(error) = error else {
+        panic!("expected a validation error, got {error:?}");
+    };
+    assert_eq!(error.issues(), [ValidationIssue::MissingExecutable]);
+}
+
+#[test]
+fn build_reports_every_failed_rule() {
+    let error = LaunchAgentBuilder::default()
+        .program("bin/example")
+        .program_arguments(Vec::<String>::new())
+        .nice(42)
+        .build()
+        .unwrap_err();
+    let Error::Validation(error) = error else {
+        panic!("expected a validation error, got {error:?}");
+    };
+
+    assert_eq!(
+        error.issues(),
+        [
+            ValidationIssue::EmptyLabel,
+            ValidationIssue::RelativeProgram("bin/example".to_string()),
+            ValidationIssue::EmptyProgramArguments,
+            ValidationIssue::NiceOutOfRange(42),
+        ]
+    );
+}
+
+#[test]
+fn try_new_rejects_relative_program() {
+    assert!(LaunchAgent::try_new("com.example.test", "example").is_err());
+    assert!(LaunchAgent::try_new_with_args("com.example.test", vec![]).is_err());
+}
+
+#[test]
+fn deserialize_errors_report_the_key_path() {
+    let error = LaunchAgent::from_str(
+        r#"{ Label = "com.example.test"; KeepAlive = { SuccessfulExit = "maybe"; }; }"#,
+    )
+    .unwrap_err();
+
+    let Error::Deserialize { key_path, .. } = &error else {
+        panic!("expected a deserialize error, got {error:?}");
+    };
+    assert_eq!(key_path, "KeepAlive.SuccessfulExit");
+}
+
+#[test]
+fn load_reports_io_errors_with_the_path() {
+    let error = LaunchAgent::load("/nonexistent/com.example.missing.plist").unwrap_err();
+
+    let Error::Io { path, source } = error else {
+        panic!("expected an I/O error, got {error:?}");
+    };
+    assert_eq!(
+        path.unwrap(),
+        Path::new("/nonexistent/com.example.missing.plist")
+    );
+    assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+}
+
+const AGENT_WITH_UNKNOWN_KEYS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>com.example.test</string>
+    <key>Program</key>
+    <string>/usr/bin/example</string>
+    <key>POSIXSpawnType</key>
+    <string>Interactive</string>
+    <key>_AdditionalProperties</key>
+    <dict>
+        <key>Installed</key>
+        <date>2024-01-01T00:00:00Z</date>
+        <key>Weight</key>
+        <real>1.5</real>
+    </dict>
+</dict>
+</plist>
+"#;
+
+#[test]
+fn unknown_keys_survive_a_round_trip() {
+    let agent: LaunchAgent = AGENT_WITH_UNKNOWN_KEYS.parse().unwrap();
+
+    assert_eq!(
+        agent.extra.keys().collect::<Vec<_>>(),
+        ["POSIXSpawnType", "_AdditionalProperties"]
+    );
+    assert_eq!(
+        LaunchAgent::from_bytes(&agent.to_binary_bytes().unwrap()).unwrap(),
+        agent
+    );
+}
+
+#[test]
+fn strict_mode_rejects_unknown_keys_by_name() {
+    let error = LaunchAgent::from_bytes_strict(AGENT_WITH_UNKNOWN_KEYS.as_bytes()).unwrap_err();
+
+    let Error::UnknownKeys { keys, .. } = error else {
+        panic!("expected an unknown keys error, got {error:?}");
+    };
+    assert_eq!(keys, ["POSIXSpawnType", "_AdditionalProperties"]);
+}
+
+const AGENT_WITH_NESTED_UNKNOWN_KEYS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>com.example.test</string>
+    <key>Program</key>
+    <string>/usr/bin/example</string>
+    <key>KeepAlive</key>
+    <dict>
+        <key>SuccessfulExit</key>
+        <false/>
+        <key>AfterInitialDemand</key>
+        <true/>
+    </dict>
+    <key>MachServices</key>
+    <dict>
+        <key>com.example.service</key>
+        <dict>
+            <key>ResetAtClose</key>
+            <true/>
+            <key>Private</key>
+            <true/>
+        </dict>
+    </dict>
+    <key>Sockets</key>
+    <dict>
+        <key>Listeners</key>
+        <array>
+            <dict>
+                <key>SockServiceName</key>
+                <string>8080</string>
+            </dict>
+            <dict>
+                <key>SockServiceName</key>
+                <string>8443</string>
+                <key>VendorTLS</key>
+                <true/>
+            </dict>
+        </array>
+    </dict>
+</dict>
+</plist>
+"#;
+
+#[test]
+fn nested_unknown_keys_survive_a_round_trip() {
+    let agent: LaunchAgent = AGENT_WITH_NESTED_UNKNOWN_KEYS.parse().unwrap();
+    let saved = plist::Value::from_reader(Cursor::new(agent.to_binary_bytes().unwrap())).unwrap();
+    let original = plist::Value::from_reader(Cursor::new(AGENT_WITH_NESTED_UNKNOWN_KEYS)).unwrap();
+
+    let saved = saved.as_dictionary().unwrap();
+    let original = original.as_dictionary().unwrap();
+    assert_eq!(saved["KeepAlive"], original["KeepAlive"]);
+    assert_eq!(
+        saved["MachServices"].as_dictionary().unwrap()["com.example.service"]
+            .as_dictionary()
+            .unwrap()["Private"],
+        true.into()
+    );
+    assert_eq!(saved["Sockets"], original["Sockets"]);
+    assert_eq!(
+        LaunchAgent::from_bytes(&agent.to_binary_bytes().unwrap()).unwrap(),
+        agent
+    );
+}
+
+#[test]
+fn strict_mode_reports_nested_unknown_keys_by_key_path() {
+    let error =
+        LaunchAgent::from_bytes_strict(AGENT_WITH_NESTED_UNKNOWN_KEYS.as_bytes()).unwrap_err();
+
+    let Error::UnknownKeys { keys, .. } = error else {
+        panic!("expected an unknown keys error, got {error:?}");
+    };
+    assert_eq!(
+        keys,
+        [
+            "KeepAlive.AfterInitialDemand",
+            "MachServices.com.example.service.Private",
+            "Sockets.Listeners[1].VendorTLS",
+        ]
+    );
+}
+
+#[test]
+fn extra_keys_cannot_shadow_fields() {
+    let mut agent = LaunchAgent::new("com.example.test", "/usr/bin/example");
+    agent
+        .extra
+        .insert("Program".to_string(), "/usr/bin/other".into());
+
+    assert!(matches!(
+        agent.to_xml_string(),
+        Err(Error::Conflict { key_path, .. }) if key_path == "Program"
+    ));
+
+    let mut agent: LaunchAgent = AGENT_WITH_NESTED_UNKNOWN_KEYS.parse().unwrap();
+    let Some(plist::Value::Dictionary(keep_alive)) = agent.extra.get_mut("KeepAlive") else {
+        panic!("expected unknown KeepAlive keys");
+    };
+    keep_alive.insert("SuccessfulExit".to_string(), true.into());
+
+    assert!(matches!(
+        agent.to_xml_string(),
+        Err(Error::Conflict { key_path, .. }) if key_path == "KeepAlive.SuccessfulExit"
+    ));
+}
+
+#[test]
+fn keys_are_written_in_canonical_order() {
+    let mut agent = LaunchAgentBuilder::default()
+        .label("com.example.test")
+        .program("/usr/bin/example")
+        .run_at_load(true)
+        .environment_variables(BTreeMap::from([
+            ("PATH".to_string(), "/usr/bin".to_string()),
+            ("HOME".to_string(), "/var/empty".to_string()),
+        ]))
+        .build()
+        .unwrap();
+    agent.extra.insert("AAA".to_string(), true.into());
+
+    let xml = agent.to_xml_string().unwrap();
+    let keys: Vec<&str> = xml
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("<key>")?.strip_suffix("</key>"))
+        .collect();
+
+    assert_eq!(
+        keys,
+        [
+            "Label",
+            "AAA",
+            "EnvironmentVariables",
+            "HOME",
+            "PATH",
+            "Program",
+            "RunAtLoad"
+        ]
+    );
+}
+
+#[test]
+fn umask_is_written_as_an_integer() {
+    let agent = LaunchAgentBuilder::default()
+        .label("com.example.test")
+        .program("/usr/bin/example")
+        .try_umask(0o022)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert!(
+        agent
+            .to_xml_string()
+            .unwrap()
+            .contains("<key>Umask</key>\n\t<integer>18</integer>")
+    );
+    assert!(LaunchAgentBuilder::default().try_umask(0o1022).is_err());
+}
+
+#[test]
+fn resource_limits_accept_human_units_and_durations() {
+    let limits = ResourceLimitsBuilder::default()
+        .try_resident_set_size("6GiB")
+        .unwrap()
+        .try_cpu(std::time::Duration::from_secs(120))
+        .unwrap()
+        .number_of_files(4096u64)
+        .build()
+        .unwrap();
+    let agent = LaunchAgentBuilder::default()
+        .label("com.example.test")
+        .program("/usr/bin/example")
+        .hard_resource_limits(limits)
+        .build()
+        .unwrap();
+
+    let xml = agent.to_xml_string().unwrap();
+
+    assert!(xml.contains("<integer>6442450944</integer>"));
+    assert!(xml.contains("<key>CPU</key>\n\t\t<integer>120</integer>"));
+    assert_eq!(xml.parse::<LaunchAgent>().unwrap(), agent);
+}
+
+#[test]
+fn session_types_round_trip_as_string_or_array() {
+    let single: LaunchAgent =
+        r#"{ Label = "com.example.test"; Program = "/usr/bin/example"; LimitLoadToSessionType = "Aqua"; }"#
+            .parse()
+            .unwrap();
+    let many: LaunchAgent = r#"{ Label = "com.example.test"; Program = "/usr/bin/example"; LimitLoadToSessionType = ("Background", "LoginWindow", "Custom"); }"#
+        .parse()
+        .unwrap();
+
+    assert_eq!(
+        single.limit_load_to_session_type,
+        Some(SessionTypes::Single(SessionType::Aqua))
+    );
+    assert_eq!(
+        many.limit_load_to_session_type.as_ref().unwrap().as_slice(),
+        [
+            SessionType::Background,
+            SessionType::LoginWindow,
+            SessionType::Other("Custom".to_string())
+        ]
+    );
+    assert!(
+        single
+            .to_xml_string()
+            .unwrap()
+            .contains("<string>Aqua</string>")
+    );
+    assert_eq!(
+        LaunchAgent::from_bytes(&many.to_binary_bytes().unwrap()).unwrap(),
+        many
+    );
+}
+
+#[test]
+fn interval_keys_accept_durations_and_strings() {
+    let agent = LaunchAgentBuilder::default()
+        .label("com.example.test")
+        .program("/usr/bin/example")
+        .try_start_interval("2h30m")
+        .unwrap()
+        .try_throttle_interval(std::time::Duration::from_secs(60))
+        .unwrap()
+        .try_exit_time_out("1m")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(agent.start_interval, Some(Interval::from(9_000)));
+    assert_eq!(
+        agent.start_interval_duration(),
+        Some(std::time::Duration::from_secs(9_000))
+    );
+    assert_eq!(
+        agent.throttle_interval_duration(),
+        Some(std::time::Duration::from_secs(60))
+    );
+    assert_eq!(
+        agent.exit_time_out_duration(),
+        Some(std::time::Duration::from_secs(60))
+    );
+    assert!(
+        agent
+            .to_xml_string()
+            .unwrap()
+            .contains("<key>StartInterval</key>\n\t<integer>9000</integer>")
+    );
+
+    let mut builder = LaunchAgentBuilder::default();
+    assert!(
+        builder
+            .try_start_interval(std::time::Duration::from_millis(1_500))
+            .is_err()
+    );
+    assert!(builder.try_start_interval("20000000000000000d").is_err());
+    assert!(
+        builder
+            .try_start_interval(std::time::Duration::from_secs(i64::MAX as u64))
+            .is_err()
+    );
+    assert!(builder.try_throttle_interval(1u64 << 32).is_err());
+    assert!(builder.try_exit_time_out("50000d").is_err());
+    assert!(
+        LaunchAgent::from_bytes(br#"{ Label = "com.example.test"; StartInterval = 4294967296; }"#)
+            .is_err()
+    );
+}
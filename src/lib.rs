@@ -1,17 +1,41 @@
 mod constraints;
 mod defaults;
+mod error;
+mod events;
+mod format;
+mod interop;
 mod ipc;
 mod keep_alive;
 mod launchagent;
+pub mod lint;
+mod modes;
+pub mod schedule;
 mod triggers;
 mod unions;
+mod units;
+mod validation;
 
-pub use constraints::{ProcessType, ResourceLimits, ResourceLimitsBuilder, SessionType};
+pub use constraints::{
+    ProcessType, ResourceLimits, ResourceLimitsBuilder, SessionType, SessionTypes,
+};
+pub use error::{Error, Result};
+pub use events::{
+    DistnotedMatching, EventDescriptor, IoKitMatching, IoKitMatchingBuilder, LaunchEvent,
+    LaunchEvents, NotifydMatching,
+};
+pub use format::Format;
 pub use ipc::{
-    Bonjour, InetdCompatibility, MachService, Socket, SocketFamily, SocketProtocol, SocketType,
-    SocketValue,
+    Bonjour, InetdCompatibility, MachService, Socket, SocketBuilder, SocketFamily, SocketProtocol,
+    SocketType, SocketValue,
+};
+pub use keep_alive::{
+    Condition, DEFAULT_THROTTLE_INTERVAL, Decision, ExitStatus, JobState, KeepAlive, Launch,
+    LaunchReason, Lifetime, RespawnSimulation, StopReason,
 };
-pub use keep_alive::KeepAlive;
 pub use launchagent::{LaunchAgent, LaunchAgentBuilder};
-pub use triggers::{CalendarInterval, CalendarIntervalBuilder};
+pub use lint::lint;
+pub use modes::{FileMode, Umask};
+pub use triggers::{CalendarInterval, CalendarIntervalBuilder, Month, Weekday};
 pub use unions::{StringOrF32, StringOrU32, StringOrVec};
+pub use units::{ByteSize, Interval, Seconds};
+pub use validation::{ValidationError, ValidationIssue};
@@ -0,0 +1,20 @@
+mod rules;
+mod structs;
+
+#[cfg(test)]
+mod tests;
+
+pub use structs::{Diagnostic, Fix, Linter, Rule, Severity};
+
+use crate::LaunchAgent;
+
+/// Checks `agent` against every rule at its default severity.
+pub fn lint(agent: &LaunchAgent) -> Vec<Diagnostic> {
+    Linter::default().lint(agent)
+}
+
+/// Applies every safe fix offered by the default rules to `agent`, returning
+/// the diagnostics that were fixed.
+pub fn fix(agent: &mut LaunchAgent) -> Vec<Diagnostic> {
+    Linter::default().fix(agent)
+}
@@ -0,0 +1,317 @@
+#![allow(deprecated)]
+
+use crate::{KeepAlive, LaunchAgent, SessionType, Socket, SocketFamily, SocketValue};
+
+use super::structs::{Fix, Rule};
+
+/// A problem found by a single rule, before the [`Linter`](super::Linter)
+/// assigns it a severity.
+pub(super) struct Finding {
+    pub key_path: String,
+    pub message: String,
+    pub fix: Option<Fix>,
+}
+
+impl Finding {
+    fn new(key_path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            key_path: key_path.into(),
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: Fix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+pub(super) fn check(rule: Rule, agent: &LaunchAgent, findings: &mut Vec<Finding>) {
+    match rule {
+        Rule::DeprecatedKey => deprecated_keys(agent, findings),
+        Rule::InfiniteExitTimeOut => infinite_exit_time_out(agent, findings),
+        Rule::KeepAliveWithLaunchOnlyOnce => keep_alive_with_launch_only_once(agent, findings),
+        Rule::UserNameInAgent => user_name_in_agent(agent, findings),
+        Rule::WatchPaths => watch_paths(agent, findings),
+        Rule::SoftLimitAboveHardLimit => soft_limit_above_hard_limit(agent, findings),
+        Rule::WorldWritableSocket => world_writable_socket(agent, findings),
+        Rule::UnknownSessionType => unknown_session_type(agent, findings),
+        Rule::InvalidCalendarInterval => invalid_calendar_interval(agent, findings),
+    }
+}
+
+const REMOVE_SERVICE_IPC: Fix = Fix::new("remove ServiceIPC", |agent| agent.service_ipc = None);
+const REMOVE_TIME_OUT: Fix = Fix::new("remove TimeOut", |agent| agent.time_out = None);
+const REMOVE_HOPEFULLY_EXITS_FIRST: Fix = Fix::new("remove HopefullyExitsFirst", |agent| {
+    agent.hopefully_exits_first = None
+});
+const REMOVE_HOPEFULLY_EXITS_LAST: Fix = Fix::new("remove HopefullyExitsLast", |agent| {
+    agent.hopefully_exits_last = None
+});
+const REMOVE_LIMIT_LOAD_TO_HOSTS: Fix = Fix::new("remove LimitLoadToHosts", |agent| {
+    agent.limit_load_to_hosts = None
+});
+const REMOVE_LIMIT_LOAD_FROM_HOSTS: Fix = Fix::new("remove LimitLoadFromHosts", |agent| {
+    agent.limit_load_from_hosts = None
+});
+const REMOVE_NETWORK_STATE: Fix = Fix::new("remove KeepAlive.NetworkState", |agent| {
+    if let Some(KeepAlive::Object { network_state, .. }) = &mut agent.keep_alive {
+        *network_state = None;
+    }
+});
+const REMOVE_ON_DEMAND: Fix = Fix::new("remove OnDemand", |agent| agent.on_demand = None);
+const REPLACE_ON_DEMAND: Fix = Fix::new("replace OnDemand with KeepAlive", |agent| {
+    if agent.on_demand == Some(false) && agent.keep_alive.is_none() {
+        agent.keep_alive = Some(KeepAlive::Bool(true));
+    }
+    agent.on_demand = None;
+});
+const REMOVE_USER_NAME: Fix = Fix::new("remove UserName", |agent| agent.user_name = None);
+const CLAMP_SOFT_LIMITS: Fix = Fix::new("lower soft resource limits to the hard limits", |agent| {
+    if let (Some(soft), Some(hard)) = (&mut agent.soft_resource_limits, &agent.hard_resource_limits)
+    {
+        soft.clamp_to(hard);
+    }
+});
+const FIX_SESSION_TYPE_CASE: Fix =
+    Fix::new("correct the capitalization of session types", |agent| {
+        if let Some(session_types) = &mut agent.limit_load_to_session_type {
+            for session_type in session_types.as_mut_slice() {
+                if let Some(known) = known_session_type(session_type) {
+                    *session_type = known;
+                }
+            }
+        }
+    });
+
+/// The documented session type that `session_type` is a miscapitalization of.
+fn known_session_type(session_type: &SessionType) -> Option<SessionType> {
+    let SessionType::Other(name) = session_type else {
+        return None;
+    };
+
+    SessionType::KNOWN
+        .into_iter()
+        .find(|known| known.as_str().eq_ignore_ascii_case(name))
+}
+
+fn deprecated_keys(agent: &LaunchAgent, findings: &mut Vec<Finding>) {
+    match agent.on_demand {
+        // `OnDemand = false` is the only form with an effect, so it can only be
+        // dropped safely when an equivalent `KeepAlive` can take its place.
+        Some(false) if agent.keep_alive.is_some() => findings.push(Finding::new(
+            "OnDemand",
+            "OnDemand is deprecated; express the same intent with KeepAlive",
+        )),
+        Some(false) => findings.push(
+            Finding::new(
+                "OnDemand",
+                "OnDemand is deprecated; OnDemand = false is equivalent to KeepAlive = true",
+            )
+            .with_fix(REPLACE_ON_DEMAND),
+        ),
+        Some(true) => findings.push(
+            Finding::new("OnDemand", "OnDemand is deprecated and true has no effect")
+                .with_fix(REMOVE_ON_DEMAND),
+        ),
+        None => {}
+    }
+
+    let unimplemented = [
+        (
+            "ServiceIPC",
+            agent.service_ipc.is_some(),
+            REMOVE_SERVICE_IPC,
+        ),
+        ("TimeOut", agent.time_out.is_some(), REMOVE_TIME_OUT),
+        (
+            "HopefullyExitsFirst",
+            agent.hopefully_exits_first.is_some(),
+            REMOVE_HOPEFULLY_EXITS_FIRST,
+        ),
+        (
+            "HopefullyExitsLast",
+            agent.hopefully_exits_last.is_some(),
+            REMOVE_HOPEFULLY_EXITS_LAST,
+        ),
+        (
+            "LimitLoadToHosts",
+            agent.limit_load_to_hosts.is_some(),
+            REMOVE_LIMIT_LOAD_TO_HOSTS,
+        ),
+        (
+            "LimitLoadFromHosts",
+            agent.limit_load_from_hosts.is_some(),
+            REMOVE_LIMIT_LOAD_FROM_HOSTS,
+        ),
+    ];
+
+    for (key, is_set, fix) in unimplemented {
+        if is_set {
+            findings.push(
+                Finding::new(
+                    key,
+                    format!("{key} is deprecated and no longer implemented"),
+                )
+                .with_fix(fix),
+            );
+        }
+    }
+
+    if let Some(KeepAlive::Object {
+        network_state: Some(_),
+        ..
+    }) = agent.keep_alive
+    {
+        findings.push(
+            Finding::new(
+                "KeepAlive.NetworkState",
+                "NetworkState is deprecated and no longer implemented",
+            )
+            .with_fix(REMOVE_NETWORK_STATE),
+        );
+    }
+}
+
+fn infinite_exit_time_out(agent: &LaunchAgent, findings: &mut Vec<Finding>) {
+    if agent
+        .exit_time_out
+        .is_some_and(|time_out| time_out.get() == 0)
+    {
+        findings.push(Finding::new(
+            "ExitTimeOut",
+            "an ExitTimeOut of 0 is treated as infinity and can stall system shutdown",
+        ));
+    }
+}
+
+fn keep_alive_with_launch_only_once(agent: &LaunchAgent, findings: &mut Vec<Finding>) {
+    let keeps_alive = !matches!(agent.keep_alive, None | Some(KeepAlive::Bool(false)));
+
+    if keeps_alive && agent.launch_only_once == Some(true) {
+        findings.push(Finding::new(
+            "KeepAlive",
+            "KeepAlive has no effect on a job with LaunchOnlyOnce, which is never respawned",
+        ));
+    }
+}
+
+fn user_name_in_agent(agent: &LaunchAgent, findings: &mut Vec<Finding>) {
+    if agent.user_name.is_some() {
+        findings.push(
+            Finding::new(
+                "UserName",
+                "UserName is ignored for agents and only applies to daemons in the system domain",
+            )
+            .with_fix(REMOVE_USER_NAME),
+        );
+    }
+}
+
+fn watch_paths(agent: &LaunchAgent, findings: &mut Vec<Finding>) {
+    if agent.watch_paths.is_some() {
+        findings.push(Finding::new(
+            "WatchPaths",
+            "WatchPaths is race-prone and may miss modifications; prefer IPC-based triggers",
+        ));
+    }
+}
+
+fn soft_limit_above_hard_limit(agent: &LaunchAgent, findings: &mut Vec<Finding>) {
+    let (Some(soft), Some(hard)) = (&agent.soft_resource_limits, &agent.hard_resource_limits)
+    else {
+        return;
+    };
+
+    for ((key, soft), (_, hard)) in soft.entries().into_iter().zip(hard.entries()) {
+        if let (Some(soft), Some(hard)) = (soft, hard)
+            && soft > hard
+        {
+            findings.push(
+                Finding::new(
+                    format!("SoftResourceLimits.{key}"),
+                    format!("the soft limit of {soft} is above the hard limit of {hard}"),
+                )
+                .with_fix(CLAMP_SOFT_LIMITS),
+            );
+        }
+    }
+}
+
+fn world_writable_socket(agent: &LaunchAgent, findings: &mut Vec<Finding>) {
+    let Some(sockets) = &agent.sockets else {
+        return;
+    };
+
+    let mut check = |key_path: String, socket: &Socket| {
+        let is_unix = socket.path_name.is_some() || socket.family == Some(SocketFamily::Unix);
+
+        if let Some(mode) = socket.path_mode
+            && is_unix
+            && mode.is_world_writable()
+        {
+            findings.push(Finding::new(
+                format!("{key_path}.SockPathMode"),
+                format!("the socket is writable by every user ({})", mode.symbolic()),
+            ));
+        }
+    };
+
+    for (name, value) in sockets {
+        match value {
+            SocketValue::Single(socket) => check(format!("Sockets.{name}"), socket),
+            SocketValue::Many(sockets) => {
+                for (index, socket) in sockets.iter().enumerate() {
+                    check(format!("Sockets.{name}.{index}"), socket);
+                }
+            }
+        }
+    }
+}
+
+fn unknown_session_type(agent: &LaunchAgent, findings: &mut Vec<Finding>) {
+    let Some(session_types) = &agent.limit_load_to_session_type else {
+        return;
+    };
+
+    for session_type in session_types.as_slice() {
+        if let SessionType::Other(name) = session_type {
+            let finding = match known_session_type(session_type) {
+                Some(known) => Finding::new(
+                    "LimitLoadToSessionType",
+                    format!("session types are case-sensitive; did you mean {known:?}?"),
+                )
+                .with_fix(FIX_SESSION_TYPE_CASE),
+                None => Finding::new(
+                    "LimitLoadToSessionType",
+                    format!("{name:?} is not a documented session type"),
+                ),
+            };
+            findings.push(finding);
+        }
+    }
+}
+
+fn invalid_calendar_interval(agent: &LaunchAgent, findings: &mut Vec<Finding>) {
+    let Some(intervals) = &agent.start_calendar_interval else {
+        return;
+    };
+
+    for (index, interval) in intervals.iter().enumerate() {
+        let key_path = format!("StartCalendarInterval.{index}");
+
+        for issue in interval.out_of_range() {
+            findings.push(Finding::new(key_path.clone(), issue.to_string()));
+        }
+
+        if let Some(message) = interval.impossible_date() {
+            let message = if interval.weekday.is_some() {
+                format!("{message}, so the job only runs on the weekday")
+            } else {
+                format!("{message}, so the job never runs")
+            };
+            findings.push(Finding::new(key_path, message));
+        }
+    }
+}
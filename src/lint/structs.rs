@@ -0,0 +1,253 @@
+use std::{collections::HashMap, fmt};
+
+use crate::LaunchAgent;
+
+use super::rules;
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Worth knowing about, but not necessarily a problem.
+    Info,
+
+    /// Likely to behave differently than intended.
+    Warning,
+
+    /// Will be rejected or misbehave when loaded by `launchd`.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+/// A check performed by the [`Linter`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Rule {
+    /// A key that `launchd` no longer implements is set.
+    DeprecatedKey,
+
+    /// [`exit_time_out`](LaunchAgent::exit_time_out) is zero, which `launchd`
+    /// treats as infinity.
+    InfiniteExitTimeOut,
+
+    /// [`keep_alive`](LaunchAgent::keep_alive) is combined with
+    /// [`launch_only_once`](LaunchAgent::launch_only_once), so the job can
+    /// never be kept alive.
+    KeepAliveWithLaunchOnlyOnce,
+
+    /// [`user_name`](LaunchAgent::user_name) is set, which agents ignore.
+    UserNameInAgent,
+
+    /// [`watch_paths`](LaunchAgent::watch_paths) is set, which is race-prone.
+    WatchPaths,
+
+    /// A [`soft_resource_limits`](LaunchAgent::soft_resource_limits) value is
+    /// greater than the matching
+    /// [`hard_resource_limits`](LaunchAgent::hard_resource_limits) value.
+    SoftLimitAboveHardLimit,
+
+    /// A Unix domain socket in [`sockets`](LaunchAgent::sockets) can be
+    /// written to by any user.
+    WorldWritableSocket,
+
+    /// [`limit_load_to_session_type`](LaunchAgent::limit_load_to_session_type)
+    /// names a session type that `launchd` does not document, so the job may
+    /// never load.
+    UnknownSessionType,
+
+    /// A [`start_calendar_interval`](LaunchAgent::start_calendar_interval)
+    /// entry has a field outside of the range `launchd` accepts, or a day
+    /// that its month never has, such as February 30.
+    InvalidCalendarInterval,
+}
+
+impl Rule {
+    /// Every rule, in the order they are checked.
+    pub const ALL: [Rule; 9] = [
+        Rule::DeprecatedKey,
+        Rule::InfiniteExitTimeOut,
+        Rule::KeepAliveWithLaunchOnlyOnce,
+        Rule::UserNameInAgent,
+        Rule::WatchPaths,
+        Rule::SoftLimitAboveHardLimit,
+        Rule::WorldWritableSocket,
+        Rule::UnknownSessionType,
+        Rule::InvalidCalendarInterval,
+    ];
+
+    /// The stable identifier of the rule.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::DeprecatedKey => "deprecated-key",
+            Self::InfiniteExitTimeOut => "infinite-exit-timeout",
+            Self::KeepAliveWithLaunchOnlyOnce => "keep-alive-with-launch-only-once",
+            Self::UserNameInAgent => "user-name-in-agent",
+            Self::WatchPaths => "watch-paths",
+            Self::SoftLimitAboveHardLimit => "soft-limit-above-hard-limit",
+            Self::WorldWritableSocket => "world-writable-socket",
+            Self::UnknownSessionType => "unknown-session-type",
+            Self::InvalidCalendarInterval => "invalid-calendar-interval",
+        }
+    }
+
+    /// The severity the rule reports at unless overridden.
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            Self::DeprecatedKey => Severity::Warning,
+            Self::InfiniteExitTimeOut => Severity::Warning,
+            Self::KeepAliveWithLaunchOnlyOnce => Severity::Warning,
+            Self::UserNameInAgent => Severity::Warning,
+            Self::WatchPaths => Severity::Info,
+            Self::SoftLimitAboveHardLimit => Severity::Error,
+            Self::WorldWritableSocket => Severity::Warning,
+            Self::UnknownSessionType => Severity::Warning,
+            Self::InvalidCalendarInterval => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// A rewrite that resolves a [`Diagnostic`] without changing how `launchd`
+/// runs the job.
+#[derive(Clone, Copy)]
+pub struct Fix {
+    description: &'static str,
+    apply: fn(&mut LaunchAgent),
+}
+
+impl Fix {
+    pub(crate) const fn new(description: &'static str, apply: fn(&mut LaunchAgent)) -> Self {
+        Self { description, apply }
+    }
+
+    /// A short, human-readable summary of the rewrite.
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+
+    /// Applies the rewrite to `agent`.
+    pub fn apply(&self, agent: &mut LaunchAgent) {
+        (self.apply)(agent)
+    }
+}
+
+impl fmt::Debug for Fix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Fix")
+            .field("description", &self.description)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A problem found by the [`Linter`].
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    /// The rule that produced the diagnostic.
+    pub rule: Rule,
+
+    /// How serious the problem is.
+    pub severity: Severity,
+
+    /// The dot-separated property list key the diagnostic points at, such as
+    /// `KeepAlive.NetworkState`.
+    pub key_path: String,
+
+    /// A human-readable description of the problem.
+    pub message: String,
+
+    /// A safe rewrite that resolves the problem, if one exists.
+    pub fix: Option<Fix>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}] {}: {}",
+            self.severity, self.rule, self.key_path, self.message
+        )
+    }
+}
+
+/// Checks a [`LaunchAgent`] against a configurable set of [`Rule`]s.
+#[derive(Clone, Debug, Default)]
+pub struct Linter {
+    overrides: HashMap<Rule, Option<Severity>>,
+}
+
+impl Linter {
+    /// Stops `rule` from being checked.
+    pub fn disable(mut self, rule: Rule) -> Self {
+        self.overrides.insert(rule, None);
+        self
+    }
+
+    /// Checks `rule` at its default severity again.
+    pub fn enable(mut self, rule: Rule) -> Self {
+        self.overrides.remove(&rule);
+        self
+    }
+
+    /// Reports `rule` at `severity` instead of its default.
+    pub fn severity(mut self, rule: Rule, severity: Severity) -> Self {
+        self.overrides.insert(rule, Some(severity));
+        self
+    }
+
+    /// The severity `rule` reports at, or `None` if it is disabled.
+    pub fn severity_of(&self, rule: Rule) -> Option<Severity> {
+        match self.overrides.get(&rule) {
+            Some(severity) => *severity,
+            None => Some(rule.default_severity()),
+        }
+    }
+
+    /// Checks `agent` against every enabled rule.
+    pub fn lint(&self, agent: &LaunchAgent) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for rule in Rule::ALL {
+            if let Some(severity) = self.severity_of(rule) {
+                let mut found = Vec::new();
+                rules::check(rule, agent, &mut found);
+                diagnostics.extend(found.into_iter().map(|finding| Diagnostic {
+                    rule,
+                    severity,
+                    key_path: finding.key_path,
+                    message: finding.message,
+                    fix: finding.fix,
+                }));
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Applies the fix of every enabled diagnostic that offers one, returning
+    /// the diagnostics that were fixed.
+    pub fn fix(&self, agent: &mut LaunchAgent) -> Vec<Diagnostic> {
+        let fixable: Vec<Diagnostic> = self
+            .lint(agent)
+            .into_iter()
+            .filter(|diagnostic| diagnostic.fix.is_some())
+            .collect();
+
+        for fix in fixable.iter().filter_map(|diagnostic| diagnostic.fix) {
+            fix.apply(agent);
+        }
+
+        fixable
+    }
+}
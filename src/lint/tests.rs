@@ -0,0 +1,212 @@
+#![allow(deprecated)]
+
+use super::*;
+use crate::{
+    Interval, KeepAlive, LaunchAgent, ResourceLimits, ResourceLimitsBuilder, SessionType,
+    SessionTypes,
+};
+use std::str::FromStr;
+
+fn agent() -> LaunchAgent {
+    LaunchAgent::new("com.example.test", "/usr/bin/example")
+}
+
+fn limits(number_of_files: u64, stack: &str) -> ResourceLimits {
+    ResourceLimitsBuilder::default()
+        .number_of_files(number_of_files)
+        .try_stack(stack)
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
+fn rules(diagnostics: &[Diagnostic]) -> Vec<Rule> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.rule)
+        .collect()
+}
+
+#[test]
+fn clean_agent_has_no_diagnostics() {
+    assert!(lint(&agent()).is_empty());
+}
+
+#[test]
+fn reports_deprecated_keys_with_key_paths() {
+    let mut agent = agent();
+    agent.service_ipc = Some(true);
+    agent.keep_alive = Some(KeepAlive::Object {
+        successful_exit: None,
+        network_state: Some(true),
+        path_state: None,
+        other_job_enabled: None,
+        crashed: None,
+    });
+
+    let diagnostics = lint(&agent);
+    let key_paths: Vec<&str> = diagnostics.iter().map(|d| d.key_path.as_str()).collect();
+
+    assert_eq!(key_paths, ["ServiceIPC", "KeepAlive.NetworkState"]);
+    assert!(diagnostics.iter().all(|d| d.rule.id() == "deprecated-key"));
+}
+
+#[test]
+fn reports_keep_alive_with_launch_only_once() {
+    let mut agent = agent();
+    agent.keep_alive = Some(KeepAlive::Bool(true));
+    agent.launch_only_once = Some(true);
+
+    assert_eq!(rules(&lint(&agent)), [Rule::KeepAliveWithLaunchOnlyOnce]);
+}
+
+#[test]
+fn reports_each_soft_limit_above_its_hard_limit() {
+    let mut agent = agent();
+    agent.soft_resource_limits = Some(limits(4096, "8MiB"));
+    agent.hard_resource_limits = Some(limits(1024, "64MiB"));
+
+    let diagnostics = lint(&agent);
+
+    assert_eq!(rules(&diagnostics), [Rule::SoftLimitAboveHardLimit]);
+    assert_eq!(diagnostics[0].key_path, "SoftResourceLimits.NumberOfFiles");
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+}
+
+#[test]
+fn rules_can_be_disabled_and_reconfigured() {
+    let mut agent = agent();
+    agent.exit_time_out = Some(Interval::from(0));
+    agent.watch_paths = Some(vec!["/tmp/trigger".to_string()]);
+
+    let diagnostics = Linter::default()
+        .disable(Rule::WatchPaths)
+        .severity(Rule::InfiniteExitTimeOut, Severity::Error)
+        .lint(&agent);
+
+    assert_eq!(rules(&diagnostics), [Rule::InfiniteExitTimeOut]);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+}
+
+#[test]
+fn fix_applies_safe_rewrites() {
+    let mut agent = agent();
+    agent.on_demand = Some(false);
+    agent.time_out = Some(30);
+    agent.user_name = Some("nobody".to_string());
+    agent.exit_time_out = Some(Interval::from(0));
+
+    let fixed = fix(&mut agent);
+
+    assert_eq!(
+        rules(&fixed),
+        [
+            Rule::DeprecatedKey,
+            Rule::DeprecatedKey,
+            Rule::UserNameInAgent
+        ]
+    );
+    assert_eq!(agent.on_demand, None);
+    assert_eq!(agent.keep_alive, Some(KeepAlive::Bool(true)));
+    assert_eq!(agent.time_out, None);
+    assert_eq!(agent.user_name, None);
+    assert_eq!(rules(&lint(&agent)), [Rule::InfiniteExitTimeOut]);
+}
+
+#[test]
+fn reports_world_writable_unix_sockets() {
+    let agent: LaunchAgent = r#"<plist version="1.0"><dict>
+        <key>Label</key><string>com.example.test</string>
+        <key>Program</key><string>/usr/bin/example</string>
+        <key>Sockets</key>
+        <dict>
+            <key>Private</key>
+            <dict>
+                <key>SockPathName</key><string>/var/run/private.sock</string>
+                <key>SockPathMode</key><integer>384</integer>
+            </dict>
+            <key>Public</key>
+            <dict>
+                <key>SockPathName</key><string>/var/run/public.sock</string>
+                <key>SockPathMode</key><integer>438</integer>
+            </dict>
+        </dict>
+    </dict></plist>"#
+        .parse()
+        .unwrap();
+
+    let diagnostics = lint(&agent);
+
+    assert_eq!(rules(&diagnostics), [Rule::WorldWritableSocket]);
+    assert_eq!(diagnostics[0].key_path, "Sockets.Public.SockPathMode");
+    assert!(diagnostics[0].message.contains("rw-rw-rw-"));
+}
+
+#[test]
+fn fix_lowers_soft_limits_to_hard_limits() {
+    let mut agent = agent();
+    agent.soft_resource_limits = Some(limits(4096, "8MiB"));
+    agent.hard_resource_limits = Some(limits(1024, "64MiB"));
+
+    fix(&mut agent);
+
+    assert_eq!(agent.soft_resource_limits, Some(limits(1024, "8MiB")));
+}
+
+#[test]
+fn fixes_miscapitalized_session_types() {
+    let mut agent = agent();
+    agent.limit_load_to_session_type = Some(SessionTypes::Many(vec![
+        SessionType::from("aqua"),
+        SessionType::from("Backround"),
+    ]));
+
+    let diagnostics = lint(&agent);
+    assert_eq!(
+        rules(&diagnostics),
+        [Rule::UnknownSessionType, Rule::UnknownSessionType]
+    );
+    assert!(diagnostics[0].fix.is_some());
+    assert!(diagnostics[1].fix.is_none());
+
+    fix(&mut agent);
+
+    assert_eq!(
+        agent.limit_load_to_session_type.unwrap().as_slice(),
+        [
+            SessionType::Aqua,
+            SessionType::Other("Backround".to_string())
+        ]
+    );
+}
+
+#[test]
+fn reports_invalid_calendar_intervals() {
+    let mut agent = agent();
+    agent.start_calendar_interval = Some(
+        LaunchAgent::from_str(
+            r#"<plist version="1.0"><dict>
+                <key>Label</key><string>com.example.test</string>
+                <key>Program</key><string>/usr/bin/example</string>
+                <key>StartCalendarInterval</key><array>
+                    <dict><key>Minute</key><integer>75</integer></dict>
+                    <dict><key>Month</key><integer>2</integer><key>Day</key><integer>30</integer></dict>
+                    <dict><key>Month</key><integer>2</integer><key>Day</key><integer>29</integer></dict>
+                </array>
+            </dict></plist>"#,
+        )
+        .unwrap()
+        .start_calendar_interval
+        .unwrap(),
+    );
+
+    let diagnostics = lint(&agent);
+
+    assert_eq!(
+        rules(&diagnostics),
+        [Rule::InvalidCalendarInterval, Rule::InvalidCalendarInterval]
+    );
+    assert_eq!(diagnostics[0].key_path, "StartCalendarInterval.0");
+    assert_eq!(diagnostics[1].key_path, "StartCalendarInterval.1");
+    assert!(diagnostics[1].message.contains("February 30"));
+}
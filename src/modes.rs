@@ -0,0 +1,349 @@
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, Visitor},
+};
+use std::{fmt, str::FromStr};
+
+use crate::error::Error;
+
+#[cfg(test)]
+mod tests;
+
+/// The permission bits that `umask(2)` honors.
+const PERMISSION_BITS: u16 = 0o777;
+
+/// A file mode creation mask, as passed to `umask(2)`.
+///
+/// Property lists cannot encode integers in octal, so a `Umask` is always
+/// written as a plain integer, but it is displayed and parsed in octal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Umask(u16);
+
+impl Umask {
+    /// Creates a mask from its permission bits, such as `0o022`, failing if
+    /// any bit outside of `0o777` is set.
+    pub fn new(bits: u16) -> Result<Self, Error> {
+        if bits > PERMISSION_BITS {
+            return Err(Error::InvalidValue {
+                value: format!("{bits:#o}"),
+                expected: "a umask between 000 and 0777",
+            });
+        }
+
+        Ok(Self(bits))
+    }
+
+    /// The permission bits of the mask.
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+
+    /// Interprets `s` the way `launchd` does, following the rules of
+    /// `strtoul(3)` with a base of zero.
+    ///
+    /// A `0x` prefix selects hexadecimal and a leading `0` selects octal.
+    /// Parsing stops at the first invalid character, so a string that does
+    /// not cleanly convert still produces a mask. A value with bits outside
+    /// of `0o777` is an error rather than being truncated.
+    pub fn from_strtoul(s: &str) -> Result<Self, Error> {
+        u16::try_from(strtoul(s))
+            .ok()
+            .filter(|bits| *bits <= PERMISSION_BITS)
+            .map(Self)
+            .ok_or_else(|| Error::InvalidValue {
+                value: s.to_string(),
+                expected: "a umask between 000 and 0777",
+            })
+    }
+}
+
+impl TryFrom<u16> for Umask {
+    type Error = Error;
+
+    fn try_from(bits: u16) -> Result<Self, Self::Error> {
+        Self::new(bits)
+    }
+}
+
+impl From<Umask> for u16 {
+    fn from(umask: Umask) -> Self {
+        umask.0
+    }
+}
+
+impl FromStr for Umask {
+    type Err = Error;
+
+    /// Parses an octal mask written as `022` or `0o022`, or a decimal mask
+    /// such as `18`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_mode(s)
+            .filter(|bits| *bits <= PERMISSION_BITS)
+            .map(Self)
+            .ok_or_else(|| Error::InvalidValue {
+                value: s.to_string(),
+                expected: "a umask between 000 and 0777",
+            })
+    }
+}
+
+impl TryFrom<&str> for Umask {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Umask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04o}", self.0)
+    }
+}
+
+impl Serialize for Umask {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0.into())
+    }
+}
+
+impl<'de> Deserialize<'de> for Umask {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct UmaskVisitor;
+
+        impl Visitor<'_> for UmaskVisitor {
+            type Value = Umask;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a umask between 000 and 0777")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Umask, E> {
+                u16::try_from(value)
+                    .ok()
+                    .filter(|bits| *bits <= PERMISSION_BITS)
+                    .map(Umask)
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Umask, E> {
+                u64::try_from(value)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+                    .and_then(|value| self.visit_u64(value))
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<Umask, E> {
+                if value.fract() == 0.0 && value >= 0.0 {
+                    self.visit_u64(value as u64)
+                } else {
+                    Err(E::invalid_value(de::Unexpected::Float(value), &self))
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Umask, E> {
+                Umask::from_strtoul(value)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_any(UmaskVisitor)
+    }
+}
+
+/// The permission, set-id and sticky bits of a file mode.
+const MODE_BITS: u16 = 0o7777;
+
+/// The permission bits of a file, as passed to `chmod(2)`.
+///
+/// Property lists cannot encode integers in octal, so a `FileMode` is always
+/// written as a plain integer, but it is displayed and parsed in octal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FileMode(u16);
+
+impl FileMode {
+    /// Creates a mode from its bits, such as `0o600`, failing if any bit
+    /// outside of `0o7777` is set.
+    pub fn new(bits: u16) -> Result<Self, Error> {
+        if bits > MODE_BITS {
+            return Err(Error::InvalidValue {
+                value: format!("{bits:#o}"),
+                expected: "a file mode between 0000 and 07777",
+            });
+        }
+
+        Ok(Self(bits))
+    }
+
+    /// The bits of the mode.
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+
+    /// Whether users other than the owner and group may write to the file.
+    pub const fn is_world_writable(&self) -> bool {
+        self.0 & 0o002 != 0
+    }
+
+    /// Renders the mode the way `ls -l` does, such as `rw-------`.
+    pub fn symbolic(&self) -> String {
+        let special = [(0o4000, 's', 'S'), (0o2000, 's', 'S'), (0o1000, 't', 'T')];
+        let mut symbolic = String::with_capacity(9);
+
+        for (class, (special_bit, set, unset)) in special.into_iter().enumerate() {
+            let bits = self.0 >> (6 - class * 3);
+            symbolic.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+            symbolic.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+            symbolic.push(match (bits & 0o1 != 0, self.0 & special_bit != 0) {
+                (true, true) => set,
+                (false, true) => unset,
+                (true, false) => 'x',
+                (false, false) => '-',
+            });
+        }
+
+        symbolic
+    }
+}
+
+impl TryFrom<u16> for FileMode {
+    type Error = Error;
+
+    fn try_from(bits: u16) -> Result<Self, Self::Error> {
+        Self::new(bits)
+    }
+}
+
+impl From<FileMode> for u16 {
+    fn from(mode: FileMode) -> Self {
+        mode.0
+    }
+}
+
+impl FromStr for FileMode {
+    type Err = Error;
+
+    /// Parses an octal mode written as `0600` or `0o600`, or a decimal mode
+    /// such as `384`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_mode(s)
+            .filter(|bits| *bits <= MODE_BITS)
+            .map(Self)
+            .ok_or_else(|| Error::InvalidValue {
+                value: s.to_string(),
+                expected: "a file mode between 0000 and 07777",
+            })
+    }
+}
+
+impl TryFrom<&str> for FileMode {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for FileMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04o}", self.0)
+    }
+}
+
+impl Serialize for FileMode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0.into())
+    }
+}
+
+impl<'de> Deserialize<'de> for FileMode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FileModeVisitor;
+
+        impl Visitor<'_> for FileModeVisitor {
+            type Value = FileMode;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a file mode")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<FileMode, E> {
+                u16::try_from(value)
+                    .ok()
+                    .filter(|bits| *bits <= MODE_BITS)
+                    .map(FileMode)
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<FileMode, E> {
+                u64::try_from(value)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+                    .and_then(|value| self.visit_u64(value))
+            }
+
+            // Earlier versions of this crate wrote the mode as a real.
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<FileMode, E> {
+                if value.fract() == 0.0 && value >= 0.0 {
+                    self.visit_u64(value as u64)
+                } else {
+                    Err(E::invalid_value(de::Unexpected::Float(value), &self))
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<FileMode, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(FileModeVisitor)
+    }
+}
+
+/// Parses an octal mode written as `0600` or `0o600`, or a decimal mode such
+/// as `384`.
+fn parse_mode(s: &str) -> Option<u16> {
+    let s = s.trim();
+
+    if let Some(octal) = s.strip_prefix("0o").or_else(|| s.strip_prefix("0O")) {
+        u16::from_str_radix(octal, 8).ok()
+    } else if s.len() > 1 && s.starts_with('0') {
+        u16::from_str_radix(&s[1..], 8).ok()
+    } else {
+        s.parse().ok()
+    }
+}
+
+/// Converts `s` to an integer following `strtoul(3)` with a base of zero.
+fn strtoul(s: &str) -> u64 {
+    let s = s.trim_start();
+    let (negative, s) = match s.as_bytes().first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+    let (radix, digits) = if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X"))
+        && hex.starts_with(|c: char| c.is_ascii_hexdigit())
+    {
+        (16, hex)
+    } else if s.starts_with('0') {
+        (8, s)
+    } else {
+        (10, s)
+    };
+
+    let mut value: u64 = 0;
+    for digit in digits.chars().map_while(|c| c.to_digit(radix)) {
+        match value
+            .checked_mul(radix.into())
+            .and_then(|value| value.checked_add(digit.into()))
+        {
+            Some(next) => value = next,
+            None => return u64::MAX,
+        }
+    }
+
+    if negative {
+        value.wrapping_neg()
+    } else {
+        value
+    }
+}
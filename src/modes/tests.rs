@@ -0,0 +1,114 @@
+use super::*;
+
+#[test]
+fn umask_parses_octal_and_decimal_strings() {
+    assert_eq!("022".parse::<Umask>().unwrap(), Umask::new(0o022).unwrap());
+    assert_eq!(
+        "0o077".parse::<Umask>().unwrap(),
+        Umask::new(0o077).unwrap()
+    );
+    assert_eq!("18".parse::<Umask>().unwrap(), Umask::new(0o022).unwrap());
+    assert!("0999".parse::<Umask>().is_err());
+    assert!("01000".parse::<Umask>().is_err());
+}
+
+#[test]
+fn umask_displays_in_octal() {
+    assert_eq!(Umask::new(0o022).unwrap().to_string(), "0022");
+}
+
+#[test]
+fn umask_rejects_bits_outside_the_permission_bits() {
+    assert!(Umask::new(0o1022).is_err());
+    assert!(Umask::try_from(0o1022u16).is_err());
+    assert_eq!(Umask::try_from("022").unwrap(), Umask::new(0o022).unwrap());
+}
+
+#[test]
+fn umask_follows_strtoul_for_loaded_strings() {
+    assert_eq!(
+        Umask::from_strtoul("022").unwrap(),
+        Umask::new(0o022).unwrap()
+    );
+    assert_eq!(
+        Umask::from_strtoul("0x12").unwrap(),
+        Umask::new(0o022).unwrap()
+    );
+    assert_eq!(
+        Umask::from_strtoul("18abc").unwrap(),
+        Umask::new(0o022).unwrap()
+    );
+    assert_eq!(
+        Umask::from_strtoul("077 ").unwrap(),
+        Umask::new(0o077).unwrap()
+    );
+    assert_eq!(Umask::from_strtoul("junk").unwrap(), Umask::new(0).unwrap());
+    assert_eq!(Umask::from_strtoul("0o22").unwrap(), Umask::new(0).unwrap());
+    assert!(Umask::from_strtoul("01022").is_err());
+    assert!(Umask::from_strtoul("0x10012").is_err());
+    assert!(Umask::from_strtoul("-1").is_err());
+}
+
+#[test]
+fn umask_serializes_as_an_integer() {
+    let value = plist::to_value(&Umask::new(0o022).unwrap()).unwrap();
+
+    assert_eq!(value, plist::Value::Integer(18.into()));
+    assert_eq!(
+        plist::from_value::<Umask>(&plist::Value::String("027".to_string())).unwrap(),
+        Umask::new(0o027).unwrap()
+    );
+    assert!(plist::from_value::<Umask>(&plist::Value::Integer(0o1022.into())).is_err());
+    assert!(plist::from_value::<Umask>(&plist::Value::Integer(65_554.into())).is_err());
+}
+
+#[test]
+fn file_mode_parses_octal_strings() {
+    assert_eq!(
+        "0600".parse::<FileMode>().unwrap(),
+        FileMode::new(0o600).unwrap()
+    );
+    assert_eq!(
+        "0o660".parse::<FileMode>().unwrap(),
+        FileMode::new(0o660).unwrap()
+    );
+    assert!("0800".parse::<FileMode>().is_err());
+}
+
+#[test]
+fn file_mode_renders_symbolically() {
+    assert_eq!(FileMode::new(0o600).unwrap().symbolic(), "rw-------");
+    assert_eq!(FileMode::new(0o755).unwrap().symbolic(), "rwxr-xr-x");
+    assert_eq!(FileMode::new(0o1777).unwrap().symbolic(), "rwxrwxrwt");
+    assert_eq!(FileMode::new(0o4644).unwrap().symbolic(), "rwSr--r--");
+}
+
+#[test]
+fn file_mode_serializes_as_an_integer() {
+    let value = plist::to_value(&FileMode::new(0o600).unwrap()).unwrap();
+
+    assert_eq!(value, plist::Value::Integer(384.into()));
+    assert_eq!(
+        plist::from_value::<FileMode>(&plist::Value::Real(384.0)).unwrap(),
+        FileMode::new(0o600).unwrap()
+    );
+}
+
+#[test]
+fn file_mode_rejects_bits_outside_the_mode_bits() {
+    assert!(FileMode::new(0o10600).is_err());
+    assert!(FileMode::try_from(0o10600u16).is_err());
+    assert_eq!(
+        FileMode::try_from("0600").unwrap(),
+        FileMode::new(0o600).unwrap()
+    );
+}
+
+#[test]
+fn socket_builder_accepts_a_mode_string() {
+    let mut builder = crate::SocketBuilder::default();
+
+    assert!(builder.try_path_mode("0600").is_ok());
+    assert!(builder.try_path_mode(0o640).is_ok());
+    assert!(builder.try_path_mode("0800").is_err());
+}
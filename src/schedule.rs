@@ -0,0 +1,25 @@
+mod calendar;
+mod compress;
+mod cron;
+mod datetime;
+mod error;
+mod human;
+pub(crate) mod pattern;
+mod report;
+mod rrule;
+mod sleep;
+mod splay;
+mod systemd;
+
+#[cfg(test)]
+mod tests;
+
+pub use calendar::{Run, Trigger};
+pub use datetime::DateTime;
+pub use error::ScheduleError;
+pub use report::{Collision, LoadReport};
+pub use sleep::{Firing, SleepWindow};
+
+/// The most calendar intervals a schedule expression may expand to unless a
+/// different limit is given.
+pub const DEFAULT_EXPANSION_LIMIT: usize = 1_000;
@@ -0,0 +1,175 @@
+use crate::{CalendarInterval, LaunchAgent};
+
+use super::{datetime::DateTime, pattern};
+
+/// How many days ahead to search before concluding that an interval never
+/// fires. Eight years covers the longest gap between two February 29ths.
+const SEARCH_DAYS: i64 = 366 * 8 + 1;
+
+/// What caused a [`Run`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trigger {
+    /// The entry at this index of
+    /// [`start_calendar_interval`](LaunchAgent::start_calendar_interval).
+    Calendar(usize),
+
+    /// [`start_interval`](LaunchAgent::start_interval).
+    Interval,
+}
+
+/// A time at which `launchd` would start a job.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Run {
+    pub at: DateTime,
+    pub trigger: Trigger,
+}
+
+impl CalendarInterval {
+    /// Whether the interval fires at some minute of `date`'s day.
+    ///
+    /// Following `launchd`, a weekday of 7 is Sunday, and when both
+    /// [`day`](Self::day) and [`weekday`](Self::weekday) are set, either one
+    /// matching is enough.
+    pub(crate) fn matches_date(&self, date: DateTime) -> bool {
+        if self.month.is_some_and(|month| month != date.month()) {
+            return false;
+        }
+
+        let day = self.day.map(|day| day == u32::from(date.day()));
+        let weekday = self.weekday.map(|weekday| weekday % 7 == date.weekday());
+
+        match (day, weekday) {
+            (Some(day), Some(weekday)) => day || weekday,
+            (Some(matches), None) | (None, Some(matches)) => matches,
+            (None, None) => true,
+        }
+    }
+
+    /// The earliest hour and minute at or after `hour:minute` on a matching
+    /// day at which the interval fires.
+    fn first_time_from(&self, hour: u32, minute: u32) -> Option<(u32, u32)> {
+        let hours = match self.hour {
+            Some(fixed) => fixed..=fixed,
+            None => 0..=23,
+        };
+
+        hours
+            .filter(|candidate| *candidate >= hour && *candidate < 24)
+            .find_map(|candidate| {
+                let earliest = if candidate == hour { minute } else { 0 };
+                match self.minute {
+                    Some(fixed) => (fixed >= earliest && fixed < 60).then_some((candidate, fixed)),
+                    None => (earliest < 60).then_some((candidate, earliest)),
+                }
+            })
+    }
+
+    /// The first time strictly after `after` at which the interval fires, or
+    /// `None` if it can never fire, such as on February 30 or when a field is
+    /// out of range.
+    ///
+    /// Calendar intervals have a resolution of one minute, so the result
+    /// always falls on the start of a minute.
+    pub fn next_after(&self, after: DateTime) -> Option<DateTime> {
+        if !pattern::in_range(std::slice::from_ref(self)) {
+            return None;
+        }
+
+        let start = after.start_of_minute().add_seconds(60)?;
+
+        (0..SEARCH_DAYS).find_map(|offset| {
+            let date = if offset == 0 {
+                start
+            } else {
+                start.start_of_day_after(offset)?
+            };
+
+            if !self.matches_date(date) {
+                return None;
+            }
+
+            let (hour, minute) = self.first_time_from(date.hour().into(), date.minute().into())?;
+            date.add_seconds(
+                (i64::from(hour) - i64::from(date.hour())) * 3_600
+                    + (i64::from(minute) - i64::from(date.minute())) * 60,
+            )
+        })
+    }
+}
+
+impl LaunchAgent {
+    /// The next `count` times `launchd` would start the job on a schedule,
+    /// assuming it was loaded at `from`.
+    ///
+    /// Runs from [`start_calendar_interval`](Self::start_calendar_interval)
+    /// and [`start_interval`](Self::start_interval) are merged into a single
+    /// timeline in chronological order. `start_interval` ticks count from
+    /// `from`, and calendar intervals that fire on the same minute are reported
+    /// once, with the first matching index. Fewer runs are returned if the
+    /// timeline reaches the last year a [`DateTime`] can hold.
+    pub fn upcoming_runs(&self, from: DateTime, count: usize) -> Vec<Run> {
+        self.runs(from).take(count).collect()
+    }
+
+    /// The timeline of runs described by
+    /// [`upcoming_runs`](Self::upcoming_runs).
+    pub(crate) fn runs(&self, from: DateTime) -> Runs<'_> {
+        let intervals = self.start_calendar_interval.as_deref().unwrap_or_default();
+        let step = self
+            .start_interval
+            .map(|step| i64::from(step.get()))
+            .filter(|step| *step > 0);
+
+        Runs {
+            intervals,
+            next_calendar: intervals
+                .iter()
+                .map(|interval| interval.next_after(from))
+                .collect(),
+            step,
+            next_interval: step.and_then(|step| from.add_seconds(step)),
+        }
+    }
+}
+
+pub(crate) struct Runs<'a> {
+    intervals: &'a [CalendarInterval],
+    next_calendar: Vec<Option<DateTime>>,
+    step: Option<i64>,
+    next_interval: Option<DateTime>,
+}
+
+impl Iterator for Runs<'_> {
+    type Item = Run;
+
+    fn next(&mut self) -> Option<Run> {
+        let calendar = self
+            .next_calendar
+            .iter()
+            .enumerate()
+            .filter_map(|(index, at)| Some(((*at)?, index)))
+            .min();
+
+        match (calendar, self.next_interval) {
+            (Some((at, index)), interval) if interval.is_none_or(|interval| at <= interval) => {
+                for (next, interval) in self.next_calendar.iter_mut().zip(self.intervals) {
+                    if *next == Some(at) {
+                        *next = interval.next_after(at);
+                    }
+                }
+                Some(Run {
+                    at,
+                    trigger: Trigger::Calendar(index),
+                })
+            }
+            (_, Some(at)) => {
+                self.next_interval = self.step.and_then(|step| at.add_seconds(step));
+                Some(Run {
+                    at,
+                    trigger: Trigger::Interval,
+                })
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::BTreeSet;
+
+use crate::{CalendarInterval, LaunchAgent};
+
+use super::pattern::{self, Field, Pattern};
+
+impl CalendarInterval {
+    /// Merges redundant calendar intervals, returning a smaller list that
+    /// matches the same times.
+    ///
+    /// Duplicates and intervals already covered by a broader one are dropped,
+    /// and intervals that together cover every value of a field are folded
+    /// into one that leaves the field unset. Sixty intervals that differ only
+    /// in their minute, for example, become a single interval that runs every
+    /// minute.
+    pub fn compress(intervals: &[Self]) -> Vec<Self> {
+        let mut compressed: Vec<Self> = Vec::new();
+        for pattern in pattern::merge(intervals) {
+            for interval in pattern.expand(usize::MAX).unwrap_or_default() {
+                if !compressed.contains(&interval) {
+                    compressed.push(interval);
+                }
+            }
+        }
+        compressed
+    }
+
+    /// Renders calendar intervals as crontab lines that match the same times,
+    /// or `None` if an interval holds a value outside of the range `launchd`
+    /// accepts.
+    ///
+    /// Intervals are merged as in [`compress`](Self::compress) and grouped so
+    /// that as few lines as possible are needed, using lists, ranges and steps.
+    /// Only the five schedule fields are rendered, without a command.
+    pub fn to_cron(intervals: &[Self]) -> Option<Vec<String>> {
+        pattern::in_range(intervals).then(|| pattern::merge(intervals).iter().map(render).collect())
+    }
+}
+
+impl LaunchAgent {
+    /// The crontab lines equivalent to
+    /// [`start_calendar_interval`](Self::start_calendar_interval), if it is
+    /// set and can be expressed in cron.
+    ///
+    /// See [`CalendarInterval::to_cron`].
+    pub fn to_cron(&self) -> Option<Vec<String>> {
+        CalendarInterval::to_cron(self.start_calendar_interval.as_deref()?)
+    }
+}
+
+/// Renders a pattern in crontab field order: minute, hour, day of month,
+/// month and day of week.
+///
+/// Cron treats a day or weekday field that starts with `*` as unrestricted and
+/// then requires the other one to match as well, so when both are restricted
+/// neither is written as a step.
+fn render(pattern: &Pattern) -> String {
+    let dates_ored = pattern.day.is_some() && pattern.weekday.is_some();
+
+    [
+        render_field(&pattern.minute, Field::MINUTE, true),
+        render_field(&pattern.hour, Field::HOUR, true),
+        render_field(&pattern.day, Field::DAY, !dates_ored),
+        render_field(&pattern.month, Field::MONTH, true),
+        render_field(&pattern.weekday, Field::WEEKDAY, !dates_ored),
+    ]
+    .join(" ")
+}
+
+/// Renders a set of values as `*`, a step such as `*/15` if `steps` allows it
+/// and it stands for at least three values, or a list of values and ranges
+/// such as `1-5,7`. Fewer values read more easily as a list, as in `0,6`
+/// rather than `*/6` for weekends.
+fn render_field(values: &Option<BTreeSet<u32>>, field: Field, steps: bool) -> String {
+    let Some(values) = values else {
+        return "*".to_string();
+    };
+
+    let range = field.distinct();
+    let step = values.iter().nth(1).map(|second| second - range.start());
+    if let Some(step) = step.filter(|step| steps && *step > 1 && values.len() >= 3)
+        && values
+            .iter()
+            .copied()
+            .eq(range.clone().step_by(step as usize))
+    {
+        return format!("*/{step}");
+    }
+
+    let mut runs: Vec<(u32, u32)> = Vec::new();
+    for &value in values {
+        match runs.last_mut() {
+            Some((_, end)) if *end + 1 == value => *end = value,
+            _ => runs.push((value, value)),
+        }
+    }
+
+    runs.iter()
+        .map(|&(start, end)| match end - start {
+            0 => start.to_string(),
+            1 => format!("{start},{end}"),
+            _ => format!("{start}-{end}"),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -0,0 +1,100 @@
+use crate::{CalendarInterval, error::Error};
+
+use super::{
+    DEFAULT_EXPANSION_LIMIT,
+    error::ScheduleError,
+    pattern::{Field, Pattern},
+};
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+impl CalendarInterval {
+    /// Converts a five-field crontab expression, such as
+    /// `*/15 9-17 * * 1-5`, into the calendar intervals that match the same
+    /// times.
+    ///
+    /// Steps, ranges, lists and three-letter month and weekday names are
+    /// expanded, as are the `@hourly`, `@daily`, `@weekly`, `@monthly` and
+    /// `@yearly` shorthands. A restricted day and weekday where either one
+    /// starts with `*`, such as `0 0 */2 * 1`, only matches when both do, which
+    /// `launchd` cannot express, so it is rejected with
+    /// [`ScheduleError::Unsupported`]. Expressions that would need more than
+    /// [`DEFAULT_EXPANSION_LIMIT`] intervals are rejected; use
+    /// [`from_cron_with_limit`](Self::from_cron_with_limit) to change the
+    /// limit.
+    ///
+    /// [`ScheduleError::Unsupported`]: super::ScheduleError::Unsupported
+    pub fn from_cron(expression: &str) -> Result<Vec<Self>, Error> {
+        Self::from_cron_with_limit(expression, DEFAULT_EXPANSION_LIMIT)
+    }
+
+    /// Like [`from_cron`](Self::from_cron), but rejects expressions that
+    /// would expand to more than `limit` calendar intervals.
+    pub fn from_cron_with_limit(expression: &str, limit: usize) -> Result<Vec<Self>, Error> {
+        parse(expression)
+            .and_then(|pattern| pattern.expand(limit))
+            .map_err(|source| Error::Schedule {
+                expression: expression.to_string(),
+                source,
+            })
+    }
+}
+
+fn parse(expression: &str) -> Result<Pattern, ScheduleError> {
+    let expression = match expression.trim() {
+        "@yearly" | "@annually" => "0 0 1 1 *",
+        "@monthly" => "0 0 1 * *",
+        "@weekly" => "0 0 * * 0",
+        "@daily" | "@midnight" => "0 0 * * *",
+        "@hourly" => "0 * * * *",
+        "@reboot" => {
+            return Err(ScheduleError::Unsupported {
+                construct: "@reboot".to_string(),
+                reason: "use RunAtLoad instead",
+            });
+        }
+        expression => expression,
+    };
+
+    let fields: Vec<&str> = expression.split_whitespace().collect();
+    let [minute, hour, day, month, weekday] = fields[..] else {
+        return Err(ScheduleError::FieldCount {
+            expected: 5,
+            found: fields.len(),
+        });
+    };
+
+    let mut days = Field::DAY.parse_list(day, "-", &[])?;
+    let mut weekdays = Field::WEEKDAY.parse_list(weekday, "-", &WEEKDAY_NAMES)?;
+
+    // Cron only runs when either the day or the weekday matches if both are
+    // restricted without a leading `*`. Otherwise both have to match, so a
+    // field that covers every value leaves the other one to decide.
+    if day.starts_with('*') || weekday.starts_with('*') {
+        for (values, field) in [(&mut days, Field::DAY), (&mut weekdays, Field::WEEKDAY)] {
+            if values.as_ref().is_some_and(|values| field.covers(values)) {
+                *values = None;
+            }
+        }
+
+        if days.is_some() && weekdays.is_some() {
+            return Err(ScheduleError::Unsupported {
+                construct: format!("{day} {month} {weekday}"),
+                reason: "cron requires both the day and the weekday to match when either \
+                         starts with `*`, but launchd runs when either one does",
+            });
+        }
+    }
+
+    Ok(Pattern {
+        minute: Field::MINUTE.parse_list(minute, "-", &[])?,
+        hour: Field::HOUR.parse_list(hour, "-", &[])?,
+        day: days,
+        weekday: weekdays,
+        month: Field::MONTH.parse_list(month, "-", &MONTH_NAMES)?,
+    })
+}
@@ -0,0 +1,233 @@
+use serde::{Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+use crate::error::Error;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// A wall-clock date and time, without a time zone.
+///
+/// `launchd` evaluates calendar intervals against the local wall clock, so
+/// schedules are computed in local time and never observe daylight saving
+/// transitions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl DateTime {
+    /// Creates a date and time, failing if any component is out of range.
+    pub fn new(
+        year: i32,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> Result<Self, Error> {
+        let valid = (1..=12).contains(&month)
+            && (1..=days_in_month(year, month)).contains(&day)
+            && hour < 24
+            && minute < 60
+            && second < 60;
+
+        if !valid {
+            return Err(Error::InvalidValue {
+                value: format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02}"),
+                expected: "a valid date and time",
+            });
+        }
+
+        Ok(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// The month, from 1 to 12.
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// The day of the month, from 1 to 31.
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// The day of the week, from 0 (Sunday) to 6 (Saturday).
+    pub fn weekday(&self) -> u8 {
+        // 1970-01-01 was a Thursday.
+        (self.days_since_epoch() + 4).rem_euclid(7) as u8
+    }
+
+    /// The number of seconds between the Unix epoch and this wall-clock time,
+    /// as if it were UTC.
+    pub(crate) fn to_seconds(self) -> i64 {
+        self.days_since_epoch() * SECONDS_PER_DAY
+            + i64::from(self.hour) * 3_600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second)
+    }
+
+    /// The inverse of [`to_seconds`](Self::to_seconds), or `None` if the
+    /// year does not fit in an `i32`.
+    pub(crate) fn from_seconds(seconds: i64) -> Option<Self> {
+        let (year, month, day) = civil_from_days(seconds.div_euclid(SECONDS_PER_DAY))?;
+        let time = seconds.rem_euclid(SECONDS_PER_DAY);
+
+        Some(Self {
+            year,
+            month,
+            day,
+            hour: (time / 3_600) as u8,
+            minute: (time % 3_600 / 60) as u8,
+            second: (time % 60) as u8,
+        })
+    }
+
+    /// The time `seconds` later (or earlier, if negative), or `None` if it
+    /// falls outside the years a `DateTime` can hold.
+    pub fn add_seconds(self, seconds: i64) -> Option<Self> {
+        Self::from_seconds(self.to_seconds().checked_add(seconds)?)
+    }
+
+    /// Midnight at the start of the day, `days` days later.
+    pub(crate) fn start_of_day_after(self, days: i64) -> Option<Self> {
+        Self::from_seconds(
+            self.days_since_epoch()
+                .checked_add(days)?
+                .checked_mul(SECONDS_PER_DAY)?,
+        )
+    }
+
+    /// The time truncated to the start of its minute.
+    pub(crate) fn start_of_minute(self) -> Self {
+        Self { second: 0, ..self }
+    }
+
+    fn days_since_epoch(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day)
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+impl Serialize for DateTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = Error;
+
+    /// Parses `YYYY-MM-DD HH:MM` or `YYYY-MM-DD HH:MM:SS`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidValue {
+            value: s.to_string(),
+            expected: "a date and time such as 2024-01-31 09:30",
+        };
+
+        let (date, time) = s.trim().split_once([' ', 'T']).ok_or_else(invalid)?;
+        let date: Vec<&str> = date.split('-').collect();
+        let time: Vec<&str> = time.split(':').collect();
+        let [year, month, day] = date[..] else {
+            return Err(invalid());
+        };
+        let (hour, minute, second) = match time[..] {
+            [hour, minute] => (hour, minute, "0"),
+            [hour, minute, second] => (hour, minute, second),
+            _ => return Err(invalid()),
+        };
+
+        Self::new(
+            year.parse().map_err(|_| invalid())?,
+            month.parse().map_err(|_| invalid())?,
+            day.parse().map_err(|_| invalid())?,
+            hour.parse().map_err(|_| invalid())?,
+            minute.parse().map_err(|_| invalid())?,
+            second.parse().map_err(|_| invalid())?,
+        )
+        .map_err(|_| invalid())
+    }
+}
+
+pub(crate) fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// The number of days in `month` (1-12) of `year`.
+pub(crate) fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Howard Hinnant's `days_from_civil`: the number of days since 1970-01-01.
+fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Howard Hinnant's `civil_from_days`: the inverse of [`days_from_civil`], or
+/// `None` if the year does not fit in an `i32`.
+fn civil_from_days(days: i64) -> Option<(i32, u8, u8)> {
+    let days = days.checked_add(719_468)?;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u8;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u8;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    Some((i32::try_from(year).ok()?, month, day))
+}
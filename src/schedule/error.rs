@@ -0,0 +1,63 @@
+use std::{error, fmt};
+
+/// Why a schedule expression could not be turned into calendar intervals.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ScheduleError {
+    /// The expression has the wrong number of fields.
+    FieldCount { expected: usize, found: usize },
+
+    /// A field could not be parsed.
+    InvalidField {
+        /// The name of the field, such as `minute`.
+        field: &'static str,
+        value: String,
+    },
+
+    /// A value lies outside of the range its field accepts.
+    OutOfRange {
+        field: &'static str,
+        value: u32,
+        min: u32,
+        max: u32,
+    },
+
+    /// Expanding the expression would produce more calendar intervals than
+    /// allowed.
+    TooManyIntervals { count: usize, limit: usize },
+
+    /// The expression uses a construct that `launchd` cannot express.
+    Unsupported {
+        /// The offending part of the expression.
+        construct: String,
+
+        /// Why it cannot be expressed, or what to use instead.
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FieldCount { expected, found } => {
+                write!(f, "expected {expected} fields, found {found}")
+            }
+            Self::InvalidField { field, value } => write!(f, "invalid {field} {value:?}"),
+            Self::OutOfRange {
+                field,
+                value,
+                min,
+                max,
+            } => write!(f, "{field} must be between {min} and {max}, got {value}"),
+            Self::TooManyIntervals { count, limit } => write!(
+                f,
+                "expands to {count} calendar intervals, more than the limit of {limit}"
+            ),
+            Self::Unsupported { construct, reason } => {
+                write!(f, "{construct:?} is not supported: {reason}")
+            }
+        }
+    }
+}
+
+impl error::Error for ScheduleError {}
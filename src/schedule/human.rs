@@ -0,0 +1,500 @@
+use std::collections::BTreeSet;
+
+use crate::{CalendarInterval, Month, Weekday, error::Error};
+
+use super::{
+    DEFAULT_EXPANSION_LIMIT,
+    error::ScheduleError,
+    pattern::{self, Field, Pattern},
+};
+
+const ORDINAL_WORDS: [&str; 10] = [
+    "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth", "tenth",
+];
+
+const WEEKDAYS: [u32; 5] = [1, 2, 3, 4, 5];
+const WEEKENDS: [u32; 2] = [0, 6];
+
+impl CalendarInterval {
+    /// Converts an English description of a schedule, such as
+    /// `every weekday at 9:30 and 17:00`, into calendar intervals.
+    ///
+    /// The usual phrasings are understood: `every minute`, `hourly` or
+    /// `every hour at :15`, `every 15 minutes` or `every 2 hours`, `every
+    /// minute during the 9:00 hour`, `daily at 6am`, weekday names,
+    /// `weekdays` and `weekends`, days of the month such as `the 1st and
+    /// 15th`, `monthly`, `yearly`, and named months such as `every day in
+    /// January`. Times may be written as `9:30`, `5pm`, `noon` or
+    /// `midnight`, and default to midnight. Several clauses can be separated
+    /// with semicolons, as [`describe`](Self::describe) does, and `never`
+    /// gives no intervals at all.
+    pub fn parse_human(text: &str) -> Result<Vec<Self>, Error> {
+        let mut intervals: Vec<Self> = Vec::new();
+
+        if text
+            .trim()
+            .trim_end_matches('.')
+            .eq_ignore_ascii_case("never")
+        {
+            return Ok(intervals);
+        }
+
+        for clause in text.split(';').filter(|clause| !clause.trim().is_empty()) {
+            let parsed = parse_clause(clause).map_err(|source| Error::Schedule {
+                expression: text.to_string(),
+                source,
+            })?;
+            for interval in parsed {
+                if !intervals.contains(&interval) {
+                    intervals.push(interval);
+                }
+            }
+        }
+
+        if intervals.len() > DEFAULT_EXPANSION_LIMIT {
+            return Err(Error::Schedule {
+                expression: text.to_string(),
+                source: ScheduleError::TooManyIntervals {
+                    count: intervals.len(),
+                    limit: DEFAULT_EXPANSION_LIMIT,
+                },
+            });
+        }
+
+        Ok(intervals)
+    }
+
+    /// Describes calendar intervals as an English sentence, such as
+    /// `Every weekday at 9:30 and 17:00.`
+    ///
+    /// Intervals are merged as in [`compress`](Self::compress) first, and the
+    /// sentence can be read back with [`parse_human`](Self::parse_human).
+    /// Intervals with a field outside of the range `launchd` accepts never
+    /// run, so they are left out, and a schedule that never runs is
+    /// described as `Never.`
+    pub fn describe(intervals: &[Self]) -> String {
+        let intervals: Vec<Self> = intervals
+            .iter()
+            .filter(|interval| pattern::in_range(std::slice::from_ref(interval)))
+            .cloned()
+            .collect();
+        if intervals.is_empty() {
+            return "Never.".to_string();
+        }
+
+        // Clauses that run on the same days at set times are combined,
+        // listing their times together.
+        let mut clauses: Vec<(String, Times)> = Vec::new();
+        for pattern in pattern::merge(&intervals).iter().flat_map(split_dates) {
+            let days = describe_days(&pattern);
+            match (clauses.last_mut(), describe_times(&pattern)) {
+                (Some((other, Times::At(all))), Times::At(times)) if *other == days => {
+                    all.extend(times);
+                }
+                (_, times) => clauses.push((days, times)),
+            }
+        }
+
+        let sentence = clauses
+            .into_iter()
+            .map(|(days, times)| match (days.is_empty(), times) {
+                (true, Times::Repeating(times)) => times,
+                (true, Times::At(times)) => format!("every day at {}", join(&times)),
+                (false, Times::Repeating(times)) => format!("{times} {days}"),
+                (false, Times::At(times)) => format!("{days} at {}", join(&times)),
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        let mut chars = sentence.chars();
+        match chars.next() {
+            Some(first) => format!("{}{}.", first.to_uppercase(), chars.as_str()),
+            None => sentence,
+        }
+    }
+}
+
+fn parse_clause(clause: &str) -> Result<Vec<CalendarInterval>, ScheduleError> {
+    let clause = clause.to_ascii_lowercase().replace([',', '.'], " ");
+    let words: Vec<&str> = clause.split_whitespace().collect();
+
+    let invalid = |word: &str| ScheduleError::InvalidField {
+        field: "word",
+        value: word.to_string(),
+    };
+
+    let mut pattern = Pattern::default();
+    let mut times: Vec<(u32, u32)> = Vec::new();
+    let mut minutes: BTreeSet<u32> = BTreeSet::new();
+    let mut hours: BTreeSet<u32> = BTreeSet::new();
+    let (mut hourly, mut minutely, mut monthly, mut yearly) = (false, false, false, false);
+    let mut during = false;
+    let mut expect_time = false;
+    let mut listing_days = false;
+    let mut index = 0;
+
+    while let Some(&word) = words.get(index) {
+        index += 1;
+        let previous = index
+            .checked_sub(2)
+            .and_then(|index| words.get(index))
+            .copied();
+        let next = words.get(index).copied();
+
+        match word {
+            "every" | "each" | "on" | "the" | "of" | "in" | "day" | "days" | "daily" | "month"
+            | "months" | "past" => {}
+            "and" | "o'clock" | "minutes" => continue,
+            "at" => {
+                expect_time = true;
+                continue;
+            }
+            "during" => {
+                during = true;
+                continue;
+            }
+            "hour" | "hours" | "hourly" => hourly = true,
+            "minute" | "minutely" => minutely = true,
+            "monthly" => monthly = true,
+            "yearly" | "annually" => yearly = true,
+            "weekly" => insert(&mut pattern.weekday, [0]),
+            "weekday" | "weekdays" => insert(&mut pattern.weekday, WEEKDAYS),
+            "weekend" | "weekends" => insert(&mut pattern.weekday, WEEKENDS),
+            "noon" | "midday" => times.push((12, 0)),
+            "midnight" => times.push((0, 0)),
+            "last" => {
+                return Err(ScheduleError::Unsupported {
+                    construct: word.to_string(),
+                    reason: "launchd cannot count days back from the end of a month",
+                });
+            }
+            _ if word.starts_with(':') => {
+                let minute = word[1..].parse().map_err(|_| invalid(word))?;
+                minutes.insert(Field::MINUTE.check(minute)?);
+            }
+            _ if matches!(previous, Some("every" | "each"))
+                && matches!(next, Some("minutes" | "hours")) =>
+            {
+                // `every 15 minutes` repeats from the top of the hour, as
+                // `*/15` does in cron.
+                let (field, values) = match next {
+                    Some("minutes") => (Field::MINUTE, &mut minutes),
+                    _ => (Field::HOUR, &mut hours),
+                };
+                let step = word.parse().map_err(|_| invalid(word))?;
+                if !(1..=field.max).contains(&step) {
+                    return Err(ScheduleError::OutOfRange {
+                        field: field.name,
+                        value: step,
+                        min: 1,
+                        max: field.max,
+                    });
+                }
+                values.extend((field.min..=field.max).step_by(step as usize));
+                index += 1;
+            }
+            _ if next == Some("minutes") => {
+                let minute = word.parse().map_err(|_| invalid(word))?;
+                minutes.insert(Field::MINUTE.check(minute)?);
+            }
+            _ => {
+                if let Some(weekday) = weekday(word) {
+                    insert(&mut pattern.weekday, [u32::from(weekday.number())]);
+                } else if let Ok(month) = word.parse::<Month>() {
+                    insert(&mut pattern.month, [u32::from(month.number())]);
+                } else if let Some(day) = ordinal(word).filter(|_| {
+                    // Only read ordinals as days in a date, as in `the 1st
+                    // and 15th` or `first of the month`, and not in phrases
+                    // such as `every second`.
+                    listing_days || previous == Some("the") || next == Some("of")
+                }) {
+                    if let Some(next) = next.filter(|next| weekday(next).is_some()) {
+                        return Err(ScheduleError::Unsupported {
+                            construct: format!("{word} {next}"),
+                            reason: "launchd cannot pick the nth weekday of a month",
+                        });
+                    }
+                    insert(&mut pattern.day, [Field::DAY.check(day)?]);
+                    expect_time = false;
+                    listing_days = true;
+                    continue;
+                } else if expect_time || word.contains(':') || word.ends_with('m') {
+                    let meridiem = next.filter(|next| matches!(*next, "am" | "pm"));
+                    if meridiem.is_some() {
+                        index += 1;
+                    }
+                    times.push(parse_time(word, meridiem).ok_or_else(|| invalid(word))??);
+                    continue;
+                } else {
+                    return Err(invalid(word));
+                }
+            }
+        }
+
+        expect_time = false;
+        listing_days = false;
+    }
+
+    if monthly && pattern.day.is_none() && pattern.weekday.is_none() {
+        insert(&mut pattern.day, [1]);
+    }
+    if yearly {
+        if pattern.month.is_none() {
+            insert(&mut pattern.month, [1]);
+        }
+        if pattern.day.is_none() && pattern.weekday.is_none() {
+            insert(&mut pattern.day, [1]);
+        }
+    }
+
+    if pattern.day.is_some() && pattern.weekday.is_some() {
+        return Err(ScheduleError::Unsupported {
+            construct: clause.trim().to_string(),
+            reason: "launchd runs when either the day or the weekday matches, not both",
+        });
+    }
+
+    let conflict = |reason| ScheduleError::Unsupported {
+        construct: clause.trim().to_string(),
+        reason,
+    };
+    let patterns = if during {
+        if !minutely || !minutes.is_empty() || !hours.is_empty() || times.is_empty() {
+            return Err(conflict(
+                "only a job that runs every minute can run during an hour",
+            ));
+        }
+        if times.iter().any(|(_, minute)| *minute != 0) {
+            return Err(conflict("an hour must start on the hour, as in 9:00"));
+        }
+        vec![Pattern {
+            hour: Some(times.into_iter().map(|(hour, _)| hour).collect()),
+            ..pattern
+        }]
+    } else if minutely {
+        if !times.is_empty() || !minutes.is_empty() || !hours.is_empty() {
+            return Err(conflict(
+                "a job that runs every minute cannot also run at set times",
+            ));
+        }
+        vec![pattern]
+    } else if hourly || !minutes.is_empty() || !hours.is_empty() {
+        if !times.is_empty() {
+            return Err(conflict("an hourly job cannot also run at set times"));
+        }
+        if minutes.is_empty() {
+            minutes.insert(0);
+        }
+        vec![Pattern {
+            minute: Some(minutes),
+            hour: (!hours.is_empty()).then_some(hours),
+            ..pattern
+        }]
+    } else {
+        if times.is_empty() {
+            times.push((0, 0));
+        }
+        times
+            .into_iter()
+            .map(|(hour, minute)| Pattern {
+                minute: Some(BTreeSet::from([minute])),
+                hour: Some(BTreeSet::from([hour])),
+                ..pattern.clone()
+            })
+            .collect()
+    };
+
+    let mut intervals = Vec::new();
+    for pattern in patterns {
+        intervals.extend(pattern.expand(DEFAULT_EXPANSION_LIMIT)?);
+    }
+    Ok(intervals)
+}
+
+fn insert<const N: usize>(values: &mut Option<BTreeSet<u32>>, new: [u32; N]) {
+    values.get_or_insert_default().extend(new);
+}
+
+/// Parses a weekday name, allowing a plural such as `mondays`.
+fn weekday(word: &str) -> Option<Weekday> {
+    word.parse()
+        .or_else(|_| word.strip_suffix('s').unwrap_or(word).parse())
+        .ok()
+}
+
+/// Parses `1st`, `22nd` or `first` as a day of the month.
+fn ordinal(word: &str) -> Option<u32> {
+    if let Some(index) = ORDINAL_WORDS.iter().position(|ordinal| *ordinal == word) {
+        return Some(index as u32 + 1);
+    }
+
+    let digits = word.strip_suffix(['t', 'd', 'h'])?;
+    let number: u32 = digits.strip_suffix(['s', 'n', 'r', 't'])?.parse().ok()?;
+    (suffix(number) == &word[word.len() - 2..]).then_some(number)
+}
+
+/// Parses `9`, `9:30`, `17:00`, `9pm` or `9:30pm`, with an optional separate
+/// `am` or `pm`, returning `None` if the word is not a time at all.
+fn parse_time(word: &str, meridiem: Option<&str>) -> Option<Result<(u32, u32), ScheduleError>> {
+    let (clock, meridiem) = match meridiem {
+        Some(meridiem) => (word, Some(meridiem)),
+        None => match word.strip_suffix("am").or_else(|| word.strip_suffix("pm")) {
+            Some(clock) => (clock, Some(&word[clock.len()..])),
+            None => (word, None),
+        },
+    };
+
+    let (hour, minute) = clock.split_once(':').unwrap_or((clock, "0"));
+    let (hour, minute): (u32, u32) = (hour.parse().ok()?, minute.parse().ok()?);
+
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => {
+            return Some(Err(ScheduleError::OutOfRange {
+                field: "hour",
+                value: hour,
+                min: 1,
+                max: 12,
+            }));
+        }
+        Some("am") => hour % 12,
+        Some(_) => hour % 12 + 12,
+        None => hour,
+    };
+
+    Some(
+        Field::HOUR
+            .check(hour)
+            .and_then(|hour| Ok((hour, Field::MINUTE.check(minute)?))),
+    )
+}
+
+/// Splits a pattern restricting both the day and the weekday into one for
+/// each, as `launchd` runs it when either matches and
+/// [`parse_human`](CalendarInterval::parse_human) does not accept both in
+/// one clause.
+fn split_dates(pattern: &Pattern) -> Vec<Pattern> {
+    if pattern.day.is_none() || pattern.weekday.is_none() {
+        return vec![pattern.clone()];
+    }
+
+    vec![
+        Pattern {
+            weekday: None,
+            ..pattern.clone()
+        },
+        Pattern {
+            day: None,
+            ..pattern.clone()
+        },
+    ]
+}
+
+fn describe_days(pattern: &Pattern) -> String {
+    let weekdays = pattern.weekday.as_ref().map(|weekdays| {
+        if weekdays.iter().eq(WEEKDAYS.iter()) {
+            "every weekday".to_string()
+        } else if weekdays.iter().eq(WEEKENDS.iter()) {
+            "every weekend".to_string()
+        } else {
+            let names: Vec<String> = weekdays
+                .iter()
+                .map(|weekday| name_of_weekday(*weekday))
+                .collect();
+            format!("every {}", join(&names))
+        }
+    });
+
+    let months = pattern.month.as_ref().map(|months| {
+        let names: Vec<String> = months.iter().map(|month| name_of_month(*month)).collect();
+        join(&names)
+    });
+
+    let days = pattern.day.as_ref().map(|days| {
+        let ordinals: Vec<String> = days
+            .iter()
+            .map(|day| format!("{day}{}", suffix(*day)))
+            .collect();
+        format!(
+            "on the {} of {}",
+            join(&ordinals),
+            months.as_deref().unwrap_or("every month")
+        )
+    });
+
+    match (days, weekdays, months) {
+        (Some(days), _, _) => days,
+        (None, Some(weekdays), Some(months)) => format!("{weekdays} in {months}"),
+        (None, Some(weekdays), None) => weekdays,
+        (None, None, Some(months)) => format!("every day in {months}"),
+        (None, None, None) => String::new(),
+    }
+}
+
+/// When a described pattern runs within a matching day.
+enum Times {
+    /// At set times of day, such as `9:30`.
+    At(Vec<String>),
+
+    /// Repeatedly, such as `every hour`.
+    Repeating(String),
+}
+
+fn describe_times(pattern: &Pattern) -> Times {
+    match (&pattern.hour, &pattern.minute) {
+        (None, None) => Times::Repeating("every minute".to_string()),
+        (None, Some(minutes)) if minutes.iter().eq([0].iter()) => {
+            Times::Repeating("every hour".to_string())
+        }
+        (None, Some(minutes)) => {
+            let minutes: Vec<String> = minutes
+                .iter()
+                .map(|minute| format!(":{minute:02}"))
+                .collect();
+            Times::Repeating(format!("every hour at {}", join(&minutes)))
+        }
+        (Some(hours), None) => {
+            let hours: Vec<String> = hours.iter().map(|hour| format!("{hour}:00")).collect();
+            let noun = if hours.len() == 1 { "hour" } else { "hours" };
+            Times::Repeating(format!("every minute during the {} {noun}", join(&hours)))
+        }
+        (Some(hours), Some(minutes)) => Times::At(
+            hours
+                .iter()
+                .flat_map(|hour| {
+                    minutes
+                        .iter()
+                        .map(move |minute| format!("{hour}:{minute:02}"))
+                })
+                .collect(),
+        ),
+    }
+}
+
+fn name_of_weekday(number: u32) -> String {
+    Weekday::try_from(number as u8)
+        .map_or_else(|_| number.to_string(), |weekday| weekday.to_string())
+}
+
+fn name_of_month(number: u32) -> String {
+    Month::try_from(number as u8).map_or_else(|_| number.to_string(), |month| month.to_string())
+}
+
+/// The English ordinal suffix for `number`, such as `st` for 21.
+fn suffix(number: u32) -> &'static str {
+    match (number % 10, number % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+/// Joins `items` as an English list: `a`, `a and b` or `a, b and c`.
+fn join(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {last}", rest.join(", ")),
+    }
+}
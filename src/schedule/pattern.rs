@@ -0,0 +1,404 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::RangeInclusive,
+};
+
+use crate::CalendarInterval;
+
+use super::error::ScheduleError;
+
+/// The order of fields in [`Pattern::fields`].
+const FIELDS: [Field; 5] = [
+    Field::MINUTE,
+    Field::HOUR,
+    Field::DAY,
+    Field::WEEKDAY,
+    Field::MONTH,
+];
+const MINUTE: usize = 0;
+const HOUR: usize = 1;
+const DAY: usize = 2;
+const WEEKDAY: usize = 3;
+const MONTH: usize = 4;
+
+/// The name and accepted range of one calendar field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Field {
+    pub(crate) name: &'static str,
+    pub(crate) min: u32,
+    pub(crate) max: u32,
+}
+
+impl Field {
+    pub(crate) const MINUTE: Self = Self::new("minute", 0, 59);
+    pub(crate) const HOUR: Self = Self::new("hour", 0, 23);
+    pub(crate) const DAY: Self = Self::new("day", 1, 31);
+    pub(crate) const MONTH: Self = Self::new("month", 1, 12);
+
+    /// Weekdays accept 7 as well as 0 for Sunday.
+    pub(crate) const WEEKDAY: Self = Self::new("weekday", 0, 7);
+
+    pub(crate) const fn new(name: &'static str, min: u32, max: u32) -> Self {
+        Self { name, min, max }
+    }
+
+    pub(crate) fn check(&self, value: u32) -> Result<u32, ScheduleError> {
+        if (self.min..=self.max).contains(&value) {
+            Ok(value)
+        } else {
+            Err(ScheduleError::OutOfRange {
+                field: self.name,
+                value,
+                min: self.min,
+                max: self.max,
+            })
+        }
+    }
+
+    /// Parses a comma-separated list of values, ranges and steps, returning
+    /// `None` for a bare `*`.
+    ///
+    /// Ranges are written with `separator`, which is `-` in cron and `..` in
+    /// systemd. A step after a single value, as in `5/10`, repeats from that
+    /// value to the end of the field. Values may also be written as one of
+    /// `names`, which start at the field's minimum.
+    pub(crate) fn parse_list(
+        &self,
+        value: &str,
+        separator: &str,
+        names: &[&str],
+    ) -> Result<Option<BTreeSet<u32>>, ScheduleError> {
+        if value == "*" {
+            return Ok(None);
+        }
+
+        let invalid = || ScheduleError::InvalidField {
+            field: self.name,
+            value: value.to_string(),
+        };
+        let number = |value: &str| {
+            value
+                .parse()
+                .ok()
+                .or_else(|| {
+                    names
+                        .iter()
+                        .position(|name| name.eq_ignore_ascii_case(value))
+                        .map(|index| index as u32 + self.min)
+                })
+                .ok_or_else(invalid)
+        };
+
+        let mut values = BTreeSet::new();
+        for item in value.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (item, None),
+            };
+
+            let step_by = match step {
+                Some(step) => step
+                    .parse()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(invalid)?,
+                None => 1,
+            };
+
+            let (start, end) = match range.split_once(separator) {
+                _ if range == "*" => (self.min, self.max),
+                Some((start, end)) => (number(start)?, number(end)?),
+                None if step.is_some() => (number(range)?, self.max),
+                None => (number(range)?, number(range)?),
+            };
+
+            let start = self.check(start)?;
+            let end = self.check(end)?;
+            if start > end {
+                return Err(invalid());
+            }
+
+            values.extend((start..=end).step_by(step_by));
+        }
+
+        Ok(Some(values))
+    }
+
+    /// Whether `values` includes every value the field can take.
+    pub(crate) fn covers(&self, values: &BTreeSet<u32>) -> bool {
+        values.range(self.distinct()).count() == self.distinct().count()
+    }
+
+    /// The distinct values the field can take, counting both Sundays as one
+    /// weekday.
+    pub(crate) fn distinct(&self) -> RangeInclusive<u32> {
+        if *self == Self::WEEKDAY {
+            0..=6
+        } else {
+            self.min..=self.max
+        }
+    }
+}
+
+/// A set of values for each calendar field, where `None` means every value.
+///
+/// A pattern matches the times whose fields all lie in their sets, except
+/// that `day` and `weekday` follow the `launchd` and cron rule that either one
+/// matching is enough when both are restricted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Pattern {
+    pub(crate) minute: Option<BTreeSet<u32>>,
+    pub(crate) hour: Option<BTreeSet<u32>>,
+    pub(crate) day: Option<BTreeSet<u32>>,
+    pub(crate) weekday: Option<BTreeSet<u32>>,
+    pub(crate) month: Option<BTreeSet<u32>>,
+}
+
+impl Pattern {
+    /// A pattern matching exactly the times `interval` matches.
+    pub(crate) fn from_interval(interval: &CalendarInterval) -> Self {
+        let single = |value: Option<u32>| value.map(|value| BTreeSet::from([value]));
+
+        Self {
+            minute: single(interval.minute),
+            hour: single(interval.hour),
+            day: single(interval.day),
+            weekday: single(interval.weekday.map(u32::from)),
+            month: single(interval.month.map(u32::from)),
+        }
+        .normalize()
+    }
+
+    /// Replaces sets that cover the whole range of their field with `None`,
+    /// and folds weekday 7 into 0.
+    pub(crate) fn normalize(mut self) -> Self {
+        if let Some(weekday) = &mut self.weekday
+            && weekday.remove(&7)
+        {
+            weekday.insert(0);
+        }
+
+        let mut wildcards = [false; 5];
+        for ((field, values), wildcard) in FIELDS.iter().zip(self.fields()).zip(&mut wildcards) {
+            *wildcard = values.as_ref().is_some_and(|set| field.covers(set));
+        }
+
+        // Every day of the month, or every weekday, matches every date no
+        // matter what the other of the two says.
+        if wildcards[DAY] || wildcards[WEEKDAY] {
+            wildcards[DAY] = true;
+            wildcards[WEEKDAY] = true;
+        }
+
+        for (values, wildcard) in self.fields_mut().into_iter().zip(wildcards) {
+            if wildcard {
+                *values = None;
+            }
+        }
+
+        self
+    }
+
+    /// Whether every time `other` matches is also matched by `self`.
+    fn covers(&self, other: &Self) -> bool {
+        let contains =
+            |outer: &Option<BTreeSet<u32>>, inner: &Option<BTreeSet<u32>>| match (outer, inner) {
+                (None, _) => true,
+                (Some(_), None) => false,
+                (Some(outer), Some(inner)) => outer.is_superset(inner),
+            };
+
+        let dates = match (
+            self.day.is_none() && self.weekday.is_none(),
+            other.day.is_none() && other.weekday.is_none(),
+        ) {
+            (true, _) => true,
+            (false, true) => false,
+            // Each of the other's date constraints must be covered by the
+            // same kind of constraint here.
+            (false, false) => {
+                let covered = |outer: &Option<BTreeSet<u32>>, inner: &Option<BTreeSet<u32>>| {
+                    inner.is_none() || outer.is_some() && contains(outer, inner)
+                };
+                covered(&self.day, &other.day) && covered(&self.weekday, &other.weekday)
+            }
+        };
+
+        dates
+            && contains(&self.minute, &other.minute)
+            && contains(&self.hour, &other.hour)
+            && contains(&self.month, &other.month)
+    }
+
+    fn fields(&self) -> [&Option<BTreeSet<u32>>; 5] {
+        [
+            &self.minute,
+            &self.hour,
+            &self.day,
+            &self.weekday,
+            &self.month,
+        ]
+    }
+
+    fn fields_mut(&mut self) -> [&mut Option<BTreeSet<u32>>; 5] {
+        [
+            &mut self.minute,
+            &mut self.hour,
+            &mut self.day,
+            &mut self.weekday,
+            &mut self.month,
+        ]
+    }
+
+    /// The number of calendar intervals [`expand`](Self::expand) produces.
+    pub(crate) fn len(&self) -> usize {
+        let size = |values: &Option<BTreeSet<u32>>| values.as_ref().map_or(1, BTreeSet::len);
+
+        [
+            size(&self.minute),
+            size(&self.hour),
+            self.dates().len(),
+            size(&self.month),
+        ]
+        .into_iter()
+        .fold(1, usize::saturating_mul)
+    }
+
+    /// The day and weekday of each interval needed to match the pattern's
+    /// dates.
+    ///
+    /// Since either one matching is enough, a restricted day and a restricted
+    /// weekday can be listed in separate intervals rather than combined in
+    /// every pairing, whichever takes fewer.
+    fn dates(&self) -> Vec<(Option<u32>, Option<u32>)> {
+        match (&self.day, &self.weekday) {
+            (Some(days), Some(weekdays))
+                if days.len() * weekdays.len() <= days.len() + weekdays.len() =>
+            {
+                days.iter()
+                    .flat_map(|day| weekdays.iter().map(|weekday| (Some(*day), Some(*weekday))))
+                    .collect()
+            }
+            (Some(days), Some(weekdays)) => days
+                .iter()
+                .map(|day| (Some(*day), None))
+                .chain(weekdays.iter().map(|weekday| (None, Some(*weekday))))
+                .collect(),
+            (Some(days), None) => days.iter().map(|day| (Some(*day), None)).collect(),
+            (None, Some(weekdays)) => weekdays
+                .iter()
+                .map(|weekday| (None, Some(*weekday)))
+                .collect(),
+            (None, None) => vec![(None, None)],
+        }
+    }
+
+    /// The smallest set of calendar intervals matching the same times, or an
+    /// error if there would be more than `limit` of them.
+    pub(crate) fn expand(self, limit: usize) -> Result<Vec<CalendarInterval>, ScheduleError> {
+        let pattern = self.normalize();
+        let count = pattern.len();
+        if count > limit {
+            return Err(ScheduleError::TooManyIntervals { count, limit });
+        }
+
+        let options = |values: &Option<BTreeSet<u32>>| -> Vec<Option<u32>> {
+            match values {
+                Some(set) => set.iter().copied().map(Some).collect(),
+                None => vec![None],
+            }
+        };
+
+        let mut intervals = Vec::with_capacity(count);
+        for month in options(&pattern.month) {
+            for (day, weekday) in pattern.dates() {
+                for hour in options(&pattern.hour) {
+                    for minute in options(&pattern.minute) {
+                        intervals.push(CalendarInterval {
+                            minute,
+                            hour,
+                            day,
+                            weekday: weekday.map(|weekday| weekday as u8),
+                            month: month.map(|month| month as u8),
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(intervals)
+    }
+}
+
+/// Whether every field of every interval lies within the range `launchd`
+/// accepts.
+pub(crate) fn in_range(intervals: &[CalendarInterval]) -> bool {
+    intervals
+        .iter()
+        .all(|interval| interval.out_of_range().is_empty())
+}
+
+/// Groups calendar intervals into as few patterns as this crate can find,
+/// together matching the same times.
+///
+/// Patterns that differ in a single field are merged by taking the union of
+/// that field, which is repeated until nothing changes. Patterns covered by
+/// another are then dropped.
+pub(crate) fn merge(intervals: &[CalendarInterval]) -> Vec<Pattern> {
+    let mut patterns: Vec<Pattern> = intervals.iter().map(Pattern::from_interval).collect();
+
+    loop {
+        let before = patterns.len();
+        for field in [MINUTE, HOUR, WEEKDAY, DAY, MONTH] {
+            patterns = merge_field(patterns, field);
+        }
+        if patterns.len() == before {
+            break;
+        }
+    }
+
+    let mut kept: Vec<Pattern> = Vec::with_capacity(patterns.len());
+    for (index, pattern) in patterns.iter().enumerate() {
+        let covered = patterns.iter().enumerate().any(|(other, outer)| {
+            // Of two identical patterns, keep the first.
+            other != index && outer.covers(pattern) && (other < index || !pattern.covers(outer))
+        });
+        if !covered {
+            kept.push(pattern.clone());
+        }
+    }
+
+    kept
+}
+
+/// Merges patterns that are identical except in `field`, keeping the order in
+/// which they first appear.
+fn merge_field(patterns: Vec<Pattern>, field: usize) -> Vec<Pattern> {
+    let mut merged: Vec<Pattern> = Vec::with_capacity(patterns.len());
+    let mut groups: BTreeMap<Vec<Option<BTreeSet<u32>>>, usize> = BTreeMap::new();
+
+    for pattern in patterns {
+        let mut key: Vec<_> = pattern.fields().into_iter().cloned().collect();
+        // Unrestricted fields only ever merge with unrestricted fields.
+        if key[field].is_some() {
+            key[field] = Some(BTreeSet::new());
+        }
+
+        match groups.get(&key) {
+            Some(&index) => {
+                let target = &mut merged[index];
+                if let (Some(into), Some(from)) =
+                    (target.fields_mut()[field].as_mut(), pattern.fields()[field])
+                {
+                    into.extend(from);
+                }
+            }
+            None => {
+                groups.insert(key, merged.len());
+                merged.push(pattern);
+            }
+        }
+    }
+
+    merged.into_iter().map(Pattern::normalize).collect()
+}
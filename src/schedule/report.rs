@@ -0,0 +1,208 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use serde::Serialize;
+
+use crate::{LaunchAgent, Weekday};
+
+use super::datetime::DateTime;
+
+/// How many collisions [`LoadReport`]'s text form lists.
+const TOP_COLLISIONS: usize = 10;
+
+/// A weekday, hour and minute, and the labels of the agents starting then.
+type Slot = (u8, u8, u8, Vec<String>);
+
+/// A time of the week at which several agents start together.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Collision {
+    pub weekday: Weekday,
+    pub hour: u8,
+    pub minute: u8,
+
+    /// The labels of the colliding agents, sorted. Agents sharing a label
+    /// each count, so the label is listed once for each of them.
+    pub labels: Vec<String>,
+
+    /// How many times the agents collide at this time during the report's
+    /// span.
+    pub occurrences: usize,
+
+    /// The first time they collide.
+    pub first: DateTime,
+}
+
+/// When a set of agents start on a schedule, and where they pile up.
+///
+/// Runs come from [`LaunchAgent::upcoming_runs`], so every agent is assumed
+/// to be loaded at the start of the report, and
+/// [`start_interval`](LaunchAgent::start_interval) ticks count from there.
+/// Runs are grouped by minute, so agents collide when they start within the
+/// same minute.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LoadReport {
+    from: DateTime,
+    until: DateTime,
+    agents: usize,
+    runs: usize,
+    heatmap: [[usize; 24]; 7],
+    collisions: Vec<Collision>,
+}
+
+impl LoadReport {
+    /// Builds a report of every scheduled run of `agents` from `from` up to
+    /// but not including `until`.
+    ///
+    /// A span of at least a week gives every weekday and hour a chance to
+    /// show up, while monthly schedules need a span that covers the days
+    /// they run on.
+    pub fn new<'a>(
+        agents: impl IntoIterator<Item = &'a LaunchAgent>,
+        from: DateTime,
+        until: DateTime,
+    ) -> Self {
+        let agents: Vec<&LaunchAgent> = agents.into_iter().collect();
+        let mut heatmap = [[0; 24]; 7];
+        let mut runs = 0;
+        let mut by_minute: BTreeMap<DateTime, BTreeSet<usize>> = BTreeMap::new();
+
+        for (index, agent) in agents.iter().enumerate() {
+            for run in agent.runs(from).take_while(|run| run.at < until) {
+                runs += 1;
+                heatmap[usize::from(run.at.weekday())][usize::from(run.at.hour())] += 1;
+                by_minute
+                    .entry(run.at.start_of_minute())
+                    .or_default()
+                    .insert(index);
+            }
+        }
+
+        let mut slots: BTreeMap<Slot, (usize, DateTime)> = BTreeMap::new();
+        for (at, indices) in by_minute
+            .into_iter()
+            .filter(|(_, indices)| indices.len() > 1)
+        {
+            let mut labels: Vec<String> = indices
+                .into_iter()
+                .map(|index| agents[index].label.clone())
+                .collect();
+            labels.sort();
+
+            let slot = (at.weekday(), at.hour(), at.minute(), labels);
+            slots.entry(slot).or_insert((0, at)).0 += 1;
+        }
+
+        let mut collisions: Vec<Collision> = slots
+            .into_iter()
+            .map(
+                |((weekday, hour, minute, labels), (occurrences, first))| Collision {
+                    weekday: Weekday::ALL[usize::from(weekday)],
+                    hour,
+                    minute,
+                    labels,
+                    occurrences,
+                    first,
+                },
+            )
+            .collect();
+        collisions.sort_by(|a, b| {
+            b.labels
+                .len()
+                .cmp(&a.labels.len())
+                .then(b.occurrences.cmp(&a.occurrences))
+                .then(a.first.cmp(&b.first))
+        });
+
+        Self {
+            from,
+            until,
+            agents: agents.len(),
+            runs,
+            heatmap,
+            collisions,
+        }
+    }
+
+    /// The number of runs starting in each hour of each weekday, indexed by
+    /// [`Weekday::number`] and then by hour.
+    pub fn heatmap(&self) -> &[[usize; 24]; 7] {
+        &self.heatmap
+    }
+
+    /// Every time of the week at which two or more agents start together,
+    /// the most crowded first.
+    pub fn collisions(&self) -> &[Collision] {
+        &self.collisions
+    }
+
+    /// The most agents that start within the same minute: 1 if none
+    /// collide, or 0 if nothing runs at all. Useful as a release gate.
+    pub fn max_concurrency(&self) -> usize {
+        self.collisions
+            .first()
+            .map_or(usize::from(self.runs > 0), |collision| {
+                collision.labels.len()
+            })
+    }
+
+    /// The total number of runs in the report's span.
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+
+    /// The report as pretty-printed JSON.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("reports only contain plain data")
+    }
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} runs of {} agents from {} to {}",
+            self.runs, self.agents, self.from, self.until
+        )?;
+
+        writeln!(f)?;
+        write!(f, "   ")?;
+        for hour in 0..24 {
+            write!(f, " {hour:>3}")?;
+        }
+        writeln!(f)?;
+        for weekday in Weekday::ALL {
+            write!(f, "{}", &weekday.name()[..3])?;
+            for count in self.heatmap[usize::from(weekday.number())] {
+                match count {
+                    0 => write!(f, "   .")?,
+                    count => write!(f, " {count:>3}")?,
+                }
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f)?;
+        if self.collisions.is_empty() {
+            return writeln!(f, "No collisions.");
+        }
+
+        writeln!(f, "Top collisions:")?;
+        for collision in self.collisions.iter().take(TOP_COLLISIONS) {
+            writeln!(
+                f,
+                "  {} {:02}:{:02}  {} agents, {} times: {}",
+                &collision.weekday.name()[..3],
+                collision.hour,
+                collision.minute,
+                collision.labels.len(),
+                collision.occurrences,
+                collision.labels.join(", ")
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,370 @@
+use std::collections::BTreeSet;
+
+use crate::{CalendarInterval, LaunchAgent, error::Error};
+
+use super::{
+    DEFAULT_EXPANSION_LIMIT,
+    error::ScheduleError,
+    pattern::{self, Field, Pattern},
+};
+
+/// RFC 5545 weekday codes, starting from Sunday as 0.
+const WEEKDAY_CODES: [&str; 7] = ["SU", "MO", "TU", "WE", "TH", "FR", "SA"];
+
+/// The `FREQ` values that `launchd` can express, from finest to coarsest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Frequency {
+    Minutely,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Minutely => "MINUTELY",
+            Self::Hourly => "HOURLY",
+            Self::Daily => "DAILY",
+            Self::Weekly => "WEEKLY",
+            Self::Monthly => "MONTHLY",
+            Self::Yearly => "YEARLY",
+        }
+    }
+}
+
+impl CalendarInterval {
+    /// Converts an RFC 5545 recurrence rule, such as
+    /// `FREQ=WEEKLY;BYDAY=MO,WE;BYHOUR=9;BYMINUTE=30`, into the calendar
+    /// intervals that match the same times.
+    ///
+    /// The rule has no `DTSTART` to take missing parts from, so a minute or
+    /// hour finer than `FREQ` and not given by `BYMINUTE` or `BYHOUR` is
+    /// taken to be 0, and weekly, monthly and yearly rules must say which
+    /// days they run on. Rules that `launchd` cannot express, such as ones
+    /// with `COUNT`, `UNTIL` or an `INTERVAL` that does not evenly divide the
+    /// next larger unit, are rejected with [`ScheduleError::Unsupported`].
+    ///
+    /// [`ScheduleError::Unsupported`]: super::ScheduleError::Unsupported
+    pub fn from_rrule(rule: &str) -> Result<Vec<Self>, Error> {
+        Self::from_rrule_with_limit(rule, DEFAULT_EXPANSION_LIMIT)
+    }
+
+    /// Like [`from_rrule`](Self::from_rrule), but rejects rules that would
+    /// expand to more than `limit` calendar intervals.
+    pub fn from_rrule_with_limit(rule: &str, limit: usize) -> Result<Vec<Self>, Error> {
+        parse(rule)
+            .and_then(|pattern| pattern.expand(limit))
+            .map_err(|source| Error::Schedule {
+                expression: rule.to_string(),
+                source,
+            })
+    }
+
+    /// Renders calendar intervals as RFC 5545 recurrence rules that together
+    /// match the same times, or `None` if an interval holds a value outside of
+    /// the range `launchd` accepts.
+    ///
+    /// Intervals are merged as in [`compress`](Self::compress). Intervals with
+    /// both a day and a weekday run when either matches, whereas a rule needs
+    /// both, so they are split into one rule for each.
+    pub fn to_rrule(intervals: &[Self]) -> Option<Vec<String>> {
+        if !pattern::in_range(intervals) {
+            return None;
+        }
+
+        let mut rules = Vec::new();
+        for pattern in pattern::merge(intervals) {
+            if pattern.day.is_some() && pattern.weekday.is_some() {
+                rules.push(render(&Pattern {
+                    weekday: None,
+                    ..pattern.clone()
+                }));
+                rules.push(render(&Pattern {
+                    day: None,
+                    ..pattern
+                }));
+            } else {
+                rules.push(render(&pattern));
+            }
+        }
+
+        Some(rules)
+    }
+}
+
+impl LaunchAgent {
+    /// The recurrence rules equivalent to
+    /// [`start_calendar_interval`](Self::start_calendar_interval), if it is
+    /// set and can be expressed as rules.
+    ///
+    /// See [`CalendarInterval::to_rrule`].
+    pub fn to_rrule(&self) -> Option<Vec<String>> {
+        CalendarInterval::to_rrule(self.start_calendar_interval.as_deref()?)
+    }
+}
+
+fn parse(rule: &str) -> Result<Pattern, ScheduleError> {
+    let rule = rule.trim();
+    let rule = rule.strip_prefix("RRULE:").unwrap_or(rule);
+
+    let mut frequency = None;
+    let mut interval: u32 = 1;
+    let mut pattern = Pattern::default();
+
+    for part in rule.split(';').filter(|part| !part.is_empty()) {
+        let (name, value) = part
+            .split_once('=')
+            .ok_or_else(|| ScheduleError::InvalidField {
+                field: "rule part",
+                value: part.to_string(),
+            })?;
+
+        let unsupported = |reason| ScheduleError::Unsupported {
+            construct: part.to_string(),
+            reason,
+        };
+
+        match name.to_ascii_uppercase().as_str() {
+            "FREQ" => frequency = Some(parse_frequency(value, part)?),
+            "INTERVAL" => {
+                interval = value
+                    .parse()
+                    .ok()
+                    .filter(|interval| *interval > 0)
+                    .ok_or_else(|| ScheduleError::InvalidField {
+                        field: "interval",
+                        value: value.to_string(),
+                    })?;
+            }
+            "BYMINUTE" => pattern.minute = Some(parse_list(value, Field::MINUTE)?),
+            "BYHOUR" => pattern.hour = Some(parse_list(value, Field::HOUR)?),
+            "BYMONTHDAY" => {
+                if value.contains('-') {
+                    return Err(unsupported(
+                        "launchd cannot count days back from the end of a month",
+                    ));
+                }
+                pattern.day = Some(parse_list(value, Field::DAY)?);
+            }
+            "BYMONTH" => pattern.month = Some(parse_list(value, Field::MONTH)?),
+            "BYDAY" => pattern.weekday = Some(parse_weekdays(value, part)?),
+            "BYSECOND" => {
+                if parse_list(value, Field::new("second", 0, 60))? != BTreeSet::from([0]) {
+                    return Err(unsupported(
+                        "launchd calendar intervals start on whole minutes",
+                    ));
+                }
+            }
+            "WKST" => {}
+            "COUNT" => {
+                return Err(unsupported(
+                    "launchd schedules cannot stop after a number of runs",
+                ));
+            }
+            "UNTIL" => return Err(unsupported("launchd schedules cannot end on a date")),
+            "BYSETPOS" | "BYWEEKNO" | "BYYEARDAY" => {
+                return Err(unsupported("launchd calendar intervals have no equivalent"));
+            }
+            _ => {
+                return Err(ScheduleError::InvalidField {
+                    field: "rule part",
+                    value: part.to_string(),
+                });
+            }
+        }
+    }
+
+    let frequency = frequency.ok_or_else(|| ScheduleError::InvalidField {
+        field: "rule",
+        value: format!("{rule} (FREQ is required)"),
+    })?;
+
+    if pattern.day.is_some() && pattern.weekday.is_some() {
+        return Err(ScheduleError::Unsupported {
+            construct: rule.to_string(),
+            reason: "a rule requires both BYMONTHDAY and BYDAY to match, \
+                     but launchd runs when either one does",
+        });
+    }
+
+    let needs_days = |reason| ScheduleError::Unsupported {
+        construct: rule.to_string(),
+        reason,
+    };
+    match frequency {
+        Frequency::Weekly if pattern.day.is_some() => {
+            return Err(needs_days(
+                "RFC 5545 does not allow BYMONTHDAY in a weekly rule",
+            ));
+        }
+        Frequency::Weekly if pattern.weekday.is_none() => {
+            return Err(needs_days(
+                "a weekly rule needs BYDAY, as there is no start date",
+            ));
+        }
+        Frequency::Monthly if pattern.weekday.is_none() && pattern.day.is_none() => {
+            return Err(needs_days(
+                "a monthly rule needs BYMONTHDAY or BYDAY, as there is no start date",
+            ));
+        }
+        Frequency::Yearly
+            if pattern.month.is_none() || pattern.weekday.is_none() && pattern.day.is_none() =>
+        {
+            return Err(needs_days(
+                "a yearly rule needs BYMONTH and BYMONTHDAY or BYDAY, as there is no start date",
+            ));
+        }
+        _ => {}
+    }
+
+    // Fields finer than the frequency default to the start of their range,
+    // while the field the frequency counts in steps by the interval.
+    let step = |field: Field, unit: Frequency, values: &mut Option<BTreeSet<u32>>| {
+        let steps: BTreeSet<u32> = if frequency == unit {
+            field.distinct().step_by(interval as usize).collect()
+        } else if frequency > unit {
+            BTreeSet::from([field.min])
+        } else {
+            return Ok(());
+        };
+
+        if frequency == unit
+            && interval > 1
+            && !field.distinct().count().is_multiple_of(interval as usize)
+        {
+            return Err(ScheduleError::Unsupported {
+                construct: format!("INTERVAL={interval}"),
+                reason: "the interval must evenly divide the next larger unit",
+            });
+        }
+
+        *values = Some(match values.take() {
+            Some(values) if frequency == unit => &values & &steps,
+            Some(values) => values,
+            None => steps,
+        });
+        Ok(())
+    };
+
+    step(Field::MINUTE, Frequency::Minutely, &mut pattern.minute)?;
+    step(Field::HOUR, Frequency::Hourly, &mut pattern.hour)?;
+    if interval > 1 {
+        match frequency {
+            Frequency::Monthly => step(Field::MONTH, Frequency::Monthly, &mut pattern.month)?,
+            Frequency::Daily | Frequency::Weekly | Frequency::Yearly => {
+                return Err(ScheduleError::Unsupported {
+                    construct: format!("INTERVAL={interval}"),
+                    reason: "launchd cannot skip days, weeks or years",
+                });
+            }
+            Frequency::Minutely | Frequency::Hourly => {}
+        }
+    }
+
+    Ok(pattern)
+}
+
+fn parse_frequency(value: &str, part: &str) -> Result<Frequency, ScheduleError> {
+    Ok(match value.to_ascii_uppercase().as_str() {
+        "MINUTELY" => Frequency::Minutely,
+        "HOURLY" => Frequency::Hourly,
+        "DAILY" => Frequency::Daily,
+        "WEEKLY" => Frequency::Weekly,
+        "MONTHLY" => Frequency::Monthly,
+        "YEARLY" => Frequency::Yearly,
+        "SECONDLY" => {
+            return Err(ScheduleError::Unsupported {
+                construct: part.to_string(),
+                reason: "launchd calendar intervals start on whole minutes",
+            });
+        }
+        _ => {
+            return Err(ScheduleError::InvalidField {
+                field: "frequency",
+                value: value.to_string(),
+            });
+        }
+    })
+}
+
+fn parse_list(value: &str, field: Field) -> Result<BTreeSet<u32>, ScheduleError> {
+    value
+        .split(',')
+        .map(|item| {
+            let value = item.parse().map_err(|_| ScheduleError::InvalidField {
+                field: field.name,
+                value: item.to_string(),
+            })?;
+            field.check(value)
+        })
+        .collect()
+}
+
+fn parse_weekdays(value: &str, part: &str) -> Result<BTreeSet<u32>, ScheduleError> {
+    value
+        .split(',')
+        .map(|item| {
+            if let Some(index) = WEEKDAY_CODES
+                .iter()
+                .position(|code| code.eq_ignore_ascii_case(item))
+            {
+                return Ok(index as u32);
+            }
+
+            // `1MO` or `-1FR`: the nth weekday of the month.
+            let ordinal = item.len() > 2
+                && WEEKDAY_CODES
+                    .iter()
+                    .any(|code| item[item.len() - 2..].eq_ignore_ascii_case(code));
+            Err(if ordinal {
+                ScheduleError::Unsupported {
+                    construct: part.to_string(),
+                    reason: "launchd cannot pick the nth weekday of a month",
+                }
+            } else {
+                ScheduleError::InvalidField {
+                    field: Field::WEEKDAY.name,
+                    value: item.to_string(),
+                }
+            })
+        })
+        .collect()
+}
+
+/// Renders a pattern with at most one of a day and a weekday as a rule, using
+/// the coarsest frequency that its unrestricted fields allow.
+fn render(pattern: &Pattern) -> String {
+    let frequency = if pattern.minute.is_none() {
+        Frequency::Minutely
+    } else if pattern.hour.is_none() {
+        Frequency::Hourly
+    } else if pattern.weekday.is_some() {
+        Frequency::Weekly
+    } else if pattern.day.is_some() {
+        Frequency::Monthly
+    } else {
+        Frequency::Daily
+    };
+
+    let number = |value: u32| value.to_string();
+    let weekday = |value: u32| WEEKDAY_CODES[value as usize].to_string();
+
+    let mut parts = vec![format!("FREQ={}", frequency.as_str())];
+    for (name, values, name_of) in [
+        ("BYMONTH", &pattern.month, &number as &dyn Fn(u32) -> String),
+        ("BYMONTHDAY", &pattern.day, &number),
+        ("BYDAY", &pattern.weekday, &weekday),
+        ("BYHOUR", &pattern.hour, &number),
+        ("BYMINUTE", &pattern.minute, &number),
+    ] {
+        if let Some(values) = values {
+            let values: Vec<String> = values.iter().map(|value| name_of(*value)).collect();
+            parts.push(format!("{name}={}", values.join(",")));
+        }
+    }
+
+    parts.join(";")
+}
@@ -0,0 +1,135 @@
+use crate::{LaunchAgent, error::Error};
+
+use super::{calendar::Trigger, datetime::DateTime};
+
+/// A span of time during which the machine is asleep, from `start` up to but
+/// not including `end`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SleepWindow {
+    start: DateTime,
+    end: DateTime,
+}
+
+impl SleepWindow {
+    /// Creates a sleep window, failing if `end` is not after `start`.
+    pub fn new(start: DateTime, end: DateTime) -> Result<Self, Error> {
+        if end <= start {
+            return Err(Error::InvalidValue {
+                value: format!("{start} to {end}"),
+                expected: "a sleep window that ends after it starts",
+            });
+        }
+
+        Ok(Self { start, end })
+    }
+
+    pub fn start(&self) -> DateTime {
+        self.start
+    }
+
+    /// When the machine wakes up.
+    pub fn end(&self) -> DateTime {
+        self.end
+    }
+
+    fn contains(&self, at: DateTime) -> bool {
+        self.start <= at && at < self.end
+    }
+}
+
+/// A time at which the job actually starts once sleep is taken into account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Firing {
+    pub at: DateTime,
+    pub trigger: Trigger,
+
+    /// How many calendar runs were missed while asleep and coalesced into
+    /// this one, or zero if the job started on time.
+    pub missed: usize,
+}
+
+impl LaunchAgent {
+    /// The times `launchd` would actually start the job between `from`, when
+    /// it was loaded, and `until`, given that the machine sleeps during
+    /// `sleeps`.
+    ///
+    /// As with [`upcoming_runs`](Self::upcoming_runs), calendar and interval
+    /// triggers are merged into one timeline. Calendar runs that fall while
+    /// the machine is asleep are coalesced into a single run on wake, while
+    /// [`start_interval`](Self::start_interval) ticks during sleep are dropped
+    /// entirely.
+    pub fn simulate_sleep(
+        &self,
+        from: DateTime,
+        until: DateTime,
+        sleeps: &[SleepWindow],
+    ) -> Vec<Firing> {
+        let mut sleeps = sleeps.to_vec();
+        sleeps.sort_by_key(|window| window.start);
+        let sleeps = merge_overlapping(sleeps);
+
+        let mut firings: Vec<Firing> = Vec::new();
+        let mut pending: Option<Firing> = None;
+
+        for run in self.runs(from).take_while(|run| run.at < until) {
+            let asleep = sleeps.iter().find(|window| window.contains(run.at));
+
+            if let Some(firing) =
+                pending.take_if(|firing| asleep.is_none_or(|w| w.end != firing.at))
+            {
+                push(&mut firings, firing);
+            }
+
+            match (asleep, run.trigger) {
+                (None, _) => push(
+                    &mut firings,
+                    Firing {
+                        at: run.at,
+                        trigger: run.trigger,
+                        missed: 0,
+                    },
+                ),
+                (Some(_), Trigger::Interval) => {}
+                (Some(window), Trigger::Calendar(_)) => {
+                    let firing = pending.get_or_insert(Firing {
+                        at: window.end,
+                        trigger: run.trigger,
+                        missed: 0,
+                    });
+                    firing.missed += 1;
+                }
+            }
+        }
+
+        if let Some(firing) = pending.filter(|firing| firing.at < until) {
+            push(&mut firings, firing);
+        }
+
+        firings
+    }
+}
+
+/// Appends `firing`, folding it into the previous firing if a coalesced run
+/// lands on the same instant as an on-time run.
+fn push(firings: &mut Vec<Firing>, firing: Firing) {
+    match firings.last_mut() {
+        Some(last) if last.at == firing.at => {
+            if matches!(last.trigger, Trigger::Interval) {
+                last.trigger = firing.trigger;
+            }
+            last.missed += firing.missed;
+        }
+        _ => firings.push(firing),
+    }
+}
+
+fn merge_overlapping(sorted: Vec<SleepWindow>) -> Vec<SleepWindow> {
+    let mut merged: Vec<SleepWindow> = Vec::with_capacity(sorted.len());
+    for window in sorted {
+        match merged.last_mut() {
+            Some(last) if window.start <= last.end => last.end = last.end.max(window.end),
+            _ => merged.push(window),
+        }
+    }
+    merged
+}
@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use crate::{CalendarInterval, LaunchAgent};
+
+use super::pattern;
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+impl CalendarInterval {
+    /// Shifts the interval later by a reproducible number of minutes below
+    /// `window`, derived from a stable hash of `key`.
+    ///
+    /// The same key always produces the same shift, on every machine and in
+    /// every version of this crate, while different keys spread out across
+    /// the window. The day, weekday and month are never changed: an interval
+    /// with a fixed hour is never shifted past midnight, and one that runs
+    /// every hour only moves its minute within the hour. Intervals that run
+    /// every minute, or hold out-of-range values, are returned unchanged.
+    pub fn splay(&self, window: Duration, key: &str) -> Self {
+        self.shifted(offset(window, &[key]))
+    }
+
+    /// Shifts the interval later by `offset` minutes, within the limits
+    /// described by [`splay`](Self::splay).
+    fn shifted(&self, offset: u32) -> Self {
+        if !pattern::in_range(std::slice::from_ref(self)) {
+            return self.clone();
+        }
+
+        match (self.hour, self.minute) {
+            (Some(hour), Some(minute)) => {
+                let start = hour * 60 + minute;
+                let shifted = start + offset % (MINUTES_PER_DAY - start);
+                Self {
+                    hour: Some(shifted / 60),
+                    minute: Some(shifted % 60),
+                    ..self.clone()
+                }
+            }
+            (None, Some(minute)) => Self {
+                minute: Some((minute + offset) % 60),
+                ..self.clone()
+            },
+            (_, None) => self.clone(),
+        }
+    }
+}
+
+impl LaunchAgent {
+    /// Shifts every [`start_calendar_interval`](Self::start_calendar_interval)
+    /// by the same reproducible number of minutes below `window`, keyed on the
+    /// [`label`](Self::label) and, if given, an identifier for the host.
+    ///
+    /// Rolling the same agent out to many machines with their host
+    /// identifiers, such as serial numbers, gives each one a different but
+    /// stable time. See [`CalendarInterval::splay`] for how the constraints
+    /// of each interval are kept.
+    pub fn splay_schedule(&mut self, window: Duration, host_id: Option<&str>) {
+        let offset = match host_id {
+            Some(host_id) => offset(window, &[&self.label, host_id]),
+            None => offset(window, &[&self.label]),
+        };
+
+        for interval in self.start_calendar_interval.iter_mut().flatten() {
+            *interval = interval.shifted(offset);
+        }
+    }
+}
+
+/// A number of whole minutes below `window`, chosen by hashing `parts`.
+fn offset(window: Duration, parts: &[&str]) -> u32 {
+    let minutes = (window.as_secs() / 60).min(u64::from(MINUTES_PER_DAY));
+    if minutes == 0 {
+        return 0;
+    }
+
+    (fnv1a(parts) % minutes) as u32
+}
+
+/// The 64-bit FNV-1a hash of `parts`, separated by NUL bytes so that
+/// `["ab", "c"]` and `["a", "bc"]` differ.
+///
+/// Unlike the standard library's hashers, its output is fixed, which keeps
+/// splayed schedules stable across machines and releases.
+fn fnv1a(parts: &[&str]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = OFFSET_BASIS;
+    for (index, part) in parts.iter().enumerate() {
+        let separator: &[u8] = if index > 0 { &[0] } else { &[] };
+        for byte in separator.iter().chain(part.as_bytes()) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
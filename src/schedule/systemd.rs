@@ -0,0 +1,247 @@
+use std::collections::BTreeSet;
+
+use crate::{CalendarInterval, error::Error};
+
+use super::{
+    DEFAULT_EXPANSION_LIMIT,
+    error::ScheduleError,
+    pattern::{Field, Pattern},
+};
+
+/// The values of one field, where `None` means every value.
+type Values = Option<BTreeSet<u32>>;
+
+/// systemd weeks start on Monday, so Sunday is 7.
+const WEEKDAY_NAMES: [(&str, &str); 7] = [
+    ("mon", "monday"),
+    ("tue", "tuesday"),
+    ("wed", "wednesday"),
+    ("thu", "thursday"),
+    ("fri", "friday"),
+    ("sat", "saturday"),
+    ("sun", "sunday"),
+];
+
+impl CalendarInterval {
+    /// Converts a systemd calendar event, as used by `OnCalendar=` in timer
+    /// units, into the calendar intervals that match the same times.
+    ///
+    /// Both the full `Mon..Fri *-*-* 09:00:00` form and the `minutely`,
+    /// `hourly`, `daily`, `weekly`, `monthly`, `quarterly`, `semiannually`
+    /// and `yearly` shorthands are accepted. Events that `launchd` cannot
+    /// express, such as ones with a non-zero second, a specific year, a `~`
+    /// anchor counting back from the end of the month or a time zone, are
+    /// rejected with [`ScheduleError::Unsupported`].
+    ///
+    /// [`ScheduleError::Unsupported`]: super::ScheduleError::Unsupported
+    pub fn from_systemd(expression: &str) -> Result<Vec<Self>, Error> {
+        Self::from_systemd_with_limit(expression, DEFAULT_EXPANSION_LIMIT)
+    }
+
+    /// Like [`from_systemd`](Self::from_systemd), but rejects events that
+    /// would expand to more than `limit` calendar intervals.
+    pub fn from_systemd_with_limit(expression: &str, limit: usize) -> Result<Vec<Self>, Error> {
+        parse(expression)
+            .and_then(|pattern| pattern.expand(limit))
+            .map_err(|source| Error::Schedule {
+                expression: expression.to_string(),
+                source,
+            })
+    }
+}
+
+fn parse(expression: &str) -> Result<Pattern, ScheduleError> {
+    let expression = expression.trim();
+    let (head, tail) = expression
+        .split_once(char::is_whitespace)
+        .unwrap_or((expression, ""));
+    let expanded = shorthand(head).map(|head| format!("{head} {tail}"));
+    let expression = expanded.as_deref().unwrap_or(expression);
+
+    let mut tokens = expression.split_whitespace().peekable();
+    let weekday = tokens
+        .next_if(|token| token.starts_with(|c: char| c.is_ascii_alphabetic()))
+        .map(parse_weekdays)
+        .transpose()?;
+    let date = tokens.next_if(|token| token.contains('-'));
+    let time = tokens.next_if(|token| token.contains(':'));
+
+    if weekday.is_none() && date.is_none() && time.is_none() {
+        return Err(ScheduleError::InvalidField {
+            field: "calendar event",
+            value: expression.to_string(),
+        });
+    }
+
+    if let Some(token) = tokens.next() {
+        return Err(if is_time_zone(token) && tokens.next().is_none() {
+            ScheduleError::Unsupported {
+                construct: token.to_string(),
+                reason: "launchd always uses the local time zone",
+            }
+        } else {
+            ScheduleError::InvalidField {
+                field: "calendar event",
+                value: expression.to_string(),
+            }
+        });
+    }
+
+    let (month, day) = match date {
+        Some(date) => parse_date(date)?,
+        None => (None, None),
+    };
+    let (hour, minute) = match time {
+        Some(time) => parse_time(time)?,
+        None => (Some(BTreeSet::from([0])), Some(BTreeSet::from([0]))),
+    };
+
+    if weekday.is_some() && day.is_some() {
+        return Err(ScheduleError::Unsupported {
+            construct: expression.to_string(),
+            reason: "systemd requires both the weekday and the day to match, \
+                     but launchd runs when either one does",
+        });
+    }
+
+    Ok(Pattern {
+        minute,
+        hour,
+        day,
+        weekday,
+        month,
+    })
+}
+
+/// Whether `token` names a time zone, such as `UTC`, `Europe/Berlin` or
+/// `+02:00`.
+fn is_time_zone(token: &str) -> bool {
+    let offset = token.strip_prefix(['+', '-']).is_some_and(|offset| {
+        !offset.is_empty() && offset.chars().all(|c| c.is_ascii_digit() || c == ':')
+    });
+    let name = token.split('/').all(|part| {
+        part.starts_with(|c: char| c.is_ascii_alphabetic())
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "_+-".contains(c))
+    });
+
+    offset
+        || name
+            && (token.contains('/')
+                || ["UTC", "GMT", "Z"]
+                    .iter()
+                    .any(|zone| token.eq_ignore_ascii_case(zone)))
+}
+
+fn shorthand(name: &str) -> Option<&'static str> {
+    Some(match name.to_ascii_lowercase().as_str() {
+        "minutely" => "*-*-* *:*:00",
+        "hourly" => "*-*-* *:00:00",
+        "daily" => "*-*-* 00:00:00",
+        "weekly" => "Mon *-*-* 00:00:00",
+        "monthly" => "*-*-01 00:00:00",
+        "quarterly" => "*-01,04,07,10-01 00:00:00",
+        "semiannually" => "*-01,07-01 00:00:00",
+        "yearly" | "annually" => "*-01-01 00:00:00",
+        _ => return None,
+    })
+}
+
+fn parse_weekdays(token: &str) -> Result<BTreeSet<u32>, ScheduleError> {
+    let invalid = || ScheduleError::InvalidField {
+        field: Field::WEEKDAY.name,
+        value: token.to_string(),
+    };
+    let weekday = |name: &str| {
+        WEEKDAY_NAMES
+            .iter()
+            .position(|(short, long)| {
+                name.eq_ignore_ascii_case(short) || name.eq_ignore_ascii_case(long)
+            })
+            .map(|index| index as u32 + 1)
+            .ok_or_else(invalid)
+    };
+
+    let mut values = BTreeSet::new();
+    for item in token.split(',') {
+        match item.split_once("..") {
+            Some((start, end)) => {
+                let (start, end) = (weekday(start)?, weekday(end)?);
+                if start > end {
+                    return Err(invalid());
+                }
+                values.extend(start..=end);
+            }
+            None => {
+                values.insert(weekday(item)?);
+            }
+        }
+    }
+
+    Ok(values)
+}
+
+/// Parses `year-month-day` or `month-day`, where the year must be `*`.
+fn parse_date(token: &str) -> Result<(Values, Values), ScheduleError> {
+    if token.contains('~') {
+        return Err(ScheduleError::Unsupported {
+            construct: token.to_string(),
+            reason: "launchd cannot count days back from the end of a month",
+        });
+    }
+
+    let parts: Vec<&str> = token.split('-').collect();
+    let (month, day) = match parts[..] {
+        [year, month, day] => {
+            if year != "*" {
+                return Err(ScheduleError::Unsupported {
+                    construct: year.to_string(),
+                    reason: "launchd calendar intervals have no year",
+                });
+            }
+            (month, day)
+        }
+        [month, day] => (month, day),
+        _ => {
+            return Err(ScheduleError::InvalidField {
+                field: "date",
+                value: token.to_string(),
+            });
+        }
+    };
+
+    Ok((
+        Field::MONTH.parse_list(month, "..", &[])?,
+        Field::DAY.parse_list(day, "..", &[])?,
+    ))
+}
+
+/// Parses `hour:minute` or `hour:minute:second`, where the second must be
+/// zero.
+fn parse_time(token: &str) -> Result<(Values, Values), ScheduleError> {
+    let parts: Vec<&str> = token.split(':').collect();
+    let (hour, minute) = match parts[..] {
+        [hour, minute] => (hour, minute),
+        [hour, minute, second] => {
+            if second.parse::<f64>() != Ok(0.0) {
+                return Err(ScheduleError::Unsupported {
+                    construct: token.to_string(),
+                    reason: "launchd calendar intervals start on whole minutes",
+                });
+            }
+            (hour, minute)
+        }
+        _ => {
+            return Err(ScheduleError::InvalidField {
+                field: "time",
+                value: token.to_string(),
+            });
+        }
+    };
+
+    Ok((
+        Field::HOUR.parse_list(hour, "..", &[])?,
+        Field::MINUTE.parse_list(minute, "..", &[])?,
+    ))
+}
@@ -0,0 +1,1166 @@
+use crate::{CalendarInterval, CalendarIntervalBuilder, LaunchAgentBuilder, Month, Weekday};
+
+use super::*;
+
+fn at(s: &str) -> DateTime {
+    s.parse().unwrap()
+}
+
+fn interval(builder: &mut CalendarIntervalBuilder) -> CalendarInterval {
+    builder.build().unwrap()
+}
+
+#[test]
+fn datetime_knows_weekdays_and_leap_years() {
+    assert_eq!(at("2024-01-01 00:00").weekday(), 1);
+    assert_eq!(at("2000-02-29 12:00").weekday(), 2);
+    assert!(DateTime::new(2023, 2, 29, 0, 0, 0).is_err());
+    assert_eq!(
+        at("2024-12-31 23:59:30").add_seconds(30).unwrap(),
+        at("2025-01-01 00:00")
+    );
+}
+
+#[test]
+fn next_after_fills_in_wildcards() {
+    let daily = interval(
+        CalendarIntervalBuilder::default()
+            .hour(9u32)
+            .unwrap()
+            .minute(30u32)
+            .unwrap(),
+    );
+
+    assert_eq!(
+        daily.next_after(at("2024-01-01 08:00")),
+        Some(at("2024-01-01 09:30"))
+    );
+    assert_eq!(
+        daily.next_after(at("2024-01-01 09:30")),
+        Some(at("2024-01-02 09:30"))
+    );
+
+    let every_minute = CalendarInterval::default();
+    assert_eq!(
+        every_minute.next_after(at("2024-01-01 08:00:15")),
+        Some(at("2024-01-01 08:01"))
+    );
+}
+
+#[test]
+fn next_after_treats_day_and_weekday_as_either() {
+    // The 15th, or any Sunday (weekday 7).
+    let either = interval(
+        CalendarIntervalBuilder::default()
+            .day(15u32)
+            .unwrap()
+            .weekday(7u8)
+            .unwrap()
+            .hour(0u32)
+            .unwrap()
+            .minute(0u32)
+            .unwrap(),
+    );
+
+    // 2024-01-01 is a Monday, so the next Sunday is the 7th.
+    assert_eq!(
+        either.next_after(at("2024-01-01 00:00")),
+        Some(at("2024-01-07 00:00"))
+    );
+    assert_eq!(
+        either.next_after(at("2024-01-14 00:00")),
+        Some(at("2024-01-15 00:00"))
+    );
+}
+
+#[test]
+fn next_after_handles_leap_days_and_impossible_dates() {
+    let leap_day = interval(
+        CalendarIntervalBuilder::default()
+            .month(2u8)
+            .unwrap()
+            .day(29u32)
+            .unwrap(),
+    );
+    let impossible = interval(
+        CalendarIntervalBuilder::default()
+            .month(2u8)
+            .unwrap()
+            .day(30u32)
+            .unwrap(),
+    );
+
+    assert_eq!(
+        leap_day.next_after(at("2097-01-01 00:00")),
+        Some(at("2104-02-29 00:00"))
+    );
+    assert_eq!(impossible.next_after(at("2024-01-01 00:00")), None);
+}
+
+#[test]
+fn out_of_range_intervals_from_a_plist_never_fire() {
+    let agent: crate::LaunchAgent = r#"{
+        Label = "com.example.test";
+        Program = "/usr/bin/example";
+        StartCalendarInterval = (
+            { Hour = 4294967295; Minute = 0; },
+            { Weekday = 8; Hour = 9; Minute = 0; }
+        );
+    }"#
+    .parse()
+    .unwrap();
+
+    assert_eq!(agent.upcoming_runs(at("2024-01-01 00:00"), 5), []);
+}
+
+#[test]
+fn upcoming_runs_merges_calendar_and_interval_triggers() {
+    let agent = LaunchAgentBuilder::default()
+        .label("com.example.test")
+        .program("/usr/bin/example")
+        .start_interval(45 * 60u32)
+        .start_calendar_interval(vec![
+            interval(CalendarIntervalBuilder::default().minute(0u32).unwrap()),
+            interval(
+                CalendarIntervalBuilder::default()
+                    .hour(10u32)
+                    .unwrap()
+                    .minute(0u32)
+                    .unwrap(),
+            ),
+        ])
+        .build()
+        .unwrap();
+
+    let runs = agent.upcoming_runs(at("2024-01-01 09:10"), 4);
+
+    assert_eq!(
+        runs,
+        [
+            Run {
+                at: at("2024-01-01 09:55"),
+                trigger: Trigger::Interval
+            },
+            Run {
+                at: at("2024-01-01 10:00"),
+                trigger: Trigger::Calendar(0)
+            },
+            Run {
+                at: at("2024-01-01 10:40"),
+                trigger: Trigger::Interval
+            },
+            Run {
+                at: at("2024-01-01 11:00"),
+                trigger: Trigger::Calendar(0)
+            },
+        ]
+    );
+}
+
+#[test]
+fn upcoming_runs_end_at_the_last_representable_year() {
+    let agent = LaunchAgentBuilder::default()
+        .label("com.example.test")
+        .program("/usr/bin/example")
+        .start_interval(u32::MAX)
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        agent.upcoming_runs(at("2147483500-01-01 00:00"), 10).len(),
+        1
+    );
+    assert_eq!(at("2024-01-01 00:00").add_seconds(i64::MAX), None);
+}
+
+fn nightly_backup() -> crate::LaunchAgent {
+    LaunchAgentBuilder::default()
+        .label("com.example.backup")
+        .program("/usr/bin/backup")
+        .start_interval(3_600u32)
+        .start_calendar_interval(vec![
+            interval(
+                CalendarIntervalBuilder::default()
+                    .hour(2u32)
+                    .unwrap()
+                    .minute(0u32)
+                    .unwrap(),
+            ),
+            interval(
+                CalendarIntervalBuilder::default()
+                    .hour(3u32)
+                    .unwrap()
+                    .minute(0u32)
+                    .unwrap(),
+            ),
+        ])
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn simulate_sleep_coalesces_calendar_runs_and_drops_interval_ticks() {
+    let agent = nightly_backup();
+    let sleeps = [SleepWindow::new(at("2024-01-01 23:30"), at("2024-01-02 07:15")).unwrap()];
+
+    let firings = agent.simulate_sleep(at("2024-01-01 21:30"), at("2024-01-02 09:00"), &sleeps);
+
+    assert_eq!(
+        firings,
+        [
+            Firing {
+                at: at("2024-01-01 22:30"),
+                trigger: Trigger::Interval,
+                missed: 0
+            },
+            Firing {
+                at: at("2024-01-02 07:15"),
+                trigger: Trigger::Calendar(0),
+                missed: 2
+            },
+            Firing {
+                at: at("2024-01-02 07:30"),
+                trigger: Trigger::Interval,
+                missed: 0
+            },
+            Firing {
+                at: at("2024-01-02 08:30"),
+                trigger: Trigger::Interval,
+                missed: 0
+            },
+        ]
+    );
+}
+
+#[test]
+fn simulate_sleep_runs_on_time_while_awake() {
+    let agent = nightly_backup();
+    let sleeps = [
+        SleepWindow::new(at("2024-01-01 12:00"), at("2024-01-01 13:00")).unwrap(),
+        SleepWindow::new(at("2024-01-01 12:30"), at("2024-01-01 14:00")).unwrap(),
+    ];
+
+    let awake = agent.simulate_sleep(at("2024-01-01 00:30"), at("2024-01-01 04:00"), &[]);
+    let asleep = agent.simulate_sleep(at("2024-01-01 00:30"), at("2024-01-01 04:00"), &sleeps);
+
+    assert_eq!(awake, asleep);
+    assert!(awake.iter().all(|firing| firing.missed == 0));
+    assert!(SleepWindow::new(at("2024-01-01 13:00"), at("2024-01-01 12:00")).is_err());
+}
+
+fn cron_error(expression: &str) -> ScheduleError {
+    match CalendarInterval::from_cron(expression) {
+        Err(crate::Error::Schedule { source, .. }) => source,
+        other => panic!("expected a schedule error, got {other:?}"),
+    }
+}
+
+#[test]
+fn from_cron_expands_steps_ranges_and_lists() {
+    let intervals = CalendarInterval::from_cron("*/15 9-17 * * 1-5").unwrap();
+
+    assert_eq!(intervals.len(), 4 * 9 * 5);
+    assert!(
+        intervals.contains(&interval(
+            CalendarIntervalBuilder::default()
+                .minute(45u32)
+                .unwrap()
+                .hour(17u32)
+                .unwrap()
+                .weekday(5u8)
+                .unwrap()
+        ))
+    );
+    assert!(
+        intervals
+            .iter()
+            .all(|interval| interval.day.is_none() && interval.month.is_none())
+    );
+}
+
+#[test]
+fn from_cron_lists_days_and_weekdays_separately() {
+    let intervals = CalendarInterval::from_cron("0 9 1,15 * 1-5").unwrap();
+
+    assert_eq!(intervals.len(), 2 + 5);
+    assert!(
+        intervals
+            .iter()
+            .all(|interval| interval.day.is_none() || interval.weekday.is_none())
+    );
+    assert_eq!(
+        intervals
+            .iter()
+            .filter(|interval| interval.day.is_some())
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn from_cron_rejects_days_and_weekdays_that_must_both_match() {
+    for expression in ["0 0 */2 * 1", "0 0 1 * */2", "0 0 */2 * mon-fri"] {
+        assert!(
+            matches!(cron_error(expression), ScheduleError::Unsupported { .. }),
+            "{expression}"
+        );
+    }
+
+    // A starred field that covers every value leaves the other to decide.
+    assert_eq!(
+        CalendarInterval::from_cron("0 0 */1 * 1").unwrap(),
+        CalendarInterval::from_cron("0 0 * * 1").unwrap()
+    );
+}
+
+#[test]
+fn from_cron_steps_from_a_single_value_to_the_end_of_the_field() {
+    assert_eq!(
+        CalendarInterval::from_cron("5/1 0 * * *").unwrap().len(),
+        55
+    );
+    assert_eq!(
+        CalendarInterval::from_cron("0 20/2 * * *").unwrap(),
+        CalendarInterval::from_cron("0 20,22 * * *").unwrap()
+    );
+}
+
+#[test]
+fn from_cron_accepts_names_and_shorthands() {
+    assert_eq!(
+        CalendarInterval::from_cron("30 2 1 jan,Jul sun").unwrap(),
+        [
+            interval(
+                CalendarIntervalBuilder::default()
+                    .minute(30u32)
+                    .unwrap()
+                    .hour(2u32)
+                    .unwrap()
+                    .day(1u32)
+                    .unwrap()
+                    .weekday(0u8)
+                    .unwrap()
+                    .month(1u8)
+                    .unwrap()
+            ),
+            interval(
+                CalendarIntervalBuilder::default()
+                    .minute(30u32)
+                    .unwrap()
+                    .hour(2u32)
+                    .unwrap()
+                    .day(1u32)
+                    .unwrap()
+                    .weekday(0u8)
+                    .unwrap()
+                    .month(7u8)
+                    .unwrap()
+            ),
+        ]
+    );
+    assert_eq!(
+        CalendarInterval::from_cron("@daily").unwrap(),
+        [interval(
+            CalendarIntervalBuilder::default()
+                .minute(0u32)
+                .unwrap()
+                .hour(0u32)
+                .unwrap()
+        )]
+    );
+}
+
+#[test]
+fn from_cron_collapses_full_ranges_into_wildcards() {
+    assert_eq!(
+        CalendarInterval::from_cron("0-59 */1 * 1-12 0-7").unwrap(),
+        [CalendarInterval::default()]
+    );
+}
+
+#[test]
+fn from_cron_rejects_bad_expressions() {
+    assert_eq!(
+        cron_error("* * * *"),
+        ScheduleError::FieldCount {
+            expected: 5,
+            found: 4
+        }
+    );
+    assert_eq!(
+        cron_error("60 * * * *"),
+        ScheduleError::OutOfRange {
+            field: "minute",
+            value: 60,
+            min: 0,
+            max: 59
+        }
+    );
+    assert!(matches!(
+        cron_error("*/0 * * * *"),
+        ScheduleError::InvalidField {
+            field: "minute",
+            ..
+        }
+    ));
+    assert!(matches!(
+        cron_error("@reboot"),
+        ScheduleError::Unsupported { .. }
+    ));
+    assert_eq!(
+        cron_error("*/2 0-9 * * 1-5"),
+        ScheduleError::TooManyIntervals {
+            count: 30 * 10 * 5,
+            limit: 1_000
+        }
+    );
+    assert!(CalendarInterval::from_cron_with_limit("*/2 0-9 * * 1-5", 1_500).is_ok());
+}
+
+#[test]
+fn compress_folds_every_minute_into_one_interval() {
+    let every_minute: Vec<CalendarInterval> = (0..60u32)
+        .map(|minute| {
+            interval(
+                CalendarIntervalBuilder::default()
+                    .minute(minute)
+                    .unwrap()
+                    .hour(9u32)
+                    .unwrap(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        CalendarInterval::compress(&every_minute),
+        [interval(
+            CalendarIntervalBuilder::default().hour(9u32).unwrap()
+        )]
+    );
+}
+
+#[test]
+fn compress_drops_duplicates_and_covered_intervals() {
+    let intervals = [
+        interval(
+            CalendarIntervalBuilder::default()
+                .minute(0u32)
+                .unwrap()
+                .hour(9u32)
+                .unwrap()
+                .weekday(1u8)
+                .unwrap(),
+        ),
+        interval(
+            CalendarIntervalBuilder::default()
+                .minute(0u32)
+                .unwrap()
+                .hour(9u32)
+                .unwrap(),
+        ),
+        interval(
+            CalendarIntervalBuilder::default()
+                .minute(0u32)
+                .unwrap()
+                .hour(9u32)
+                .unwrap()
+                .weekday(7u8)
+                .unwrap(),
+        ),
+        interval(
+            CalendarIntervalBuilder::default()
+                .minute(0u32)
+                .unwrap()
+                .hour(9u32)
+                .unwrap(),
+        ),
+    ];
+
+    assert_eq!(
+        CalendarInterval::compress(&intervals),
+        [interval(
+            CalendarIntervalBuilder::default()
+                .minute(0u32)
+                .unwrap()
+                .hour(9u32)
+                .unwrap()
+        )]
+    );
+}
+
+#[test]
+fn compress_keeps_day_or_weekday_semantics() {
+    // Every day of the month, or Mondays, is every day.
+    let every_day: Vec<CalendarInterval> = (1..=31u32)
+        .map(|day| {
+            interval(
+                CalendarIntervalBuilder::default()
+                    .minute(0u32)
+                    .unwrap()
+                    .day(day)
+                    .unwrap()
+                    .weekday(1u8)
+                    .unwrap(),
+            )
+        })
+        .collect();
+    // The 1st, or Mondays, is not the same as the 1st alone.
+    let either = [interval(
+        CalendarIntervalBuilder::default()
+            .day(1u32)
+            .unwrap()
+            .weekday(1u8)
+            .unwrap(),
+    )];
+
+    assert_eq!(
+        CalendarInterval::compress(&every_day),
+        [interval(
+            CalendarIntervalBuilder::default().minute(0u32).unwrap()
+        )]
+    );
+    assert_eq!(CalendarInterval::compress(&either), either);
+}
+
+#[test]
+fn to_cron_round_trips_from_cron() {
+    for expression in [
+        "*/15 9-17 * * 1-5",
+        "0 2 * * *",
+        "30 6 1,15 * 0",
+        "0 0 1 */3 *",
+    ] {
+        let intervals = CalendarInterval::from_cron(expression).unwrap();
+        assert_eq!(
+            CalendarInterval::to_cron(&intervals),
+            Some(vec![expression.to_string()])
+        );
+    }
+}
+
+#[test]
+fn to_cron_avoids_steps_when_day_and_weekday_are_both_restricted() {
+    let intervals = CalendarInterval::from_cron("0 9 1-31/2 * 1").unwrap();
+
+    assert_eq!(
+        CalendarInterval::to_cron(&intervals),
+        Some(vec![
+            "0 9 1,3,5,7,9,11,13,15,17,19,21,23,25,27,29,31 * 1".to_string()
+        ])
+    );
+    assert_eq!(
+        CalendarInterval::to_cron(&CalendarInterval::from_cron("0 9 */2 * *").unwrap()),
+        Some(vec!["0 9 */2 * *".to_string()])
+    );
+}
+
+#[test]
+fn to_cron_lists_short_steps() {
+    for (expression, rendered) in [
+        ("0 10 * * 0,6", "0 10 * * 0,6"),
+        ("0 10 * * */6", "0 10 * * 0,6"),
+        ("0 */12 * * *", "0 0,12 * * *"),
+        ("0 */8 * * *", "0 */8 * * *"),
+    ] {
+        let intervals = CalendarInterval::from_cron(expression).unwrap();
+        assert_eq!(
+            CalendarInterval::to_cron(&intervals),
+            Some(vec![rendered.to_string()]),
+            "{expression}"
+        );
+    }
+}
+
+#[test]
+fn to_cron_needs_several_lines_for_unrelated_intervals() {
+    let agent = LaunchAgentBuilder::default()
+        .label("com.example.test")
+        .program("/usr/bin/example")
+        .start_calendar_interval(vec![
+            interval(
+                CalendarIntervalBuilder::default()
+                    .minute(0u32)
+                    .unwrap()
+                    .hour(9u32)
+                    .unwrap(),
+            ),
+            interval(
+                CalendarIntervalBuilder::default()
+                    .minute(30u32)
+                    .unwrap()
+                    .hour(17u32)
+                    .unwrap()
+                    .weekday(5u8)
+                    .unwrap(),
+            ),
+        ])
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        agent.to_cron(),
+        Some(vec!["0 9 * * *".to_string(), "30 17 * * 5".to_string()])
+    );
+
+    // Only a property list read from disk can hold an out-of-range value.
+    let out_of_range = CalendarInterval {
+        minute: Some(75),
+        ..CalendarInterval::default()
+    };
+    assert_eq!(CalendarInterval::to_cron(&[out_of_range]), None);
+}
+
+fn systemd_error(expression: &str) -> ScheduleError {
+    match CalendarInterval::from_systemd(expression) {
+        Err(crate::Error::Schedule { source, .. }) => source,
+        other => panic!("expected a schedule error, got {other:?}"),
+    }
+}
+
+#[test]
+fn from_systemd_matches_equivalent_cron() {
+    for (systemd, cron) in [
+        ("Mon..Fri *-*-* 09:00", "0 9 * * 1-5"),
+        ("Sat,Sun 10:30:00", "30 10 * * 6,0"),
+        ("*-*-01,15 04:00", "0 4 1,15 * *"),
+        ("*:0/15", "*/15 * * * *"),
+        ("daily", "@daily"),
+        ("Weekly", "0 0 * * 1"),
+        ("monthly", "@monthly"),
+        ("quarterly", "0 0 1 */3 *"),
+        ("12-25 08:00", "0 8 25 12 *"),
+    ] {
+        assert_eq!(
+            CalendarInterval::from_systemd(systemd).unwrap(),
+            CalendarInterval::from_cron(cron).unwrap(),
+            "{systemd}"
+        );
+    }
+}
+
+#[test]
+fn from_systemd_rejects_what_launchd_cannot_express() {
+    assert!(matches!(
+        systemd_error("*-*-* 09:00:30"),
+        ScheduleError::Unsupported { construct, .. } if construct == "09:00:30"
+    ));
+    assert!(matches!(
+        systemd_error("2025-*-* 09:00"),
+        ScheduleError::Unsupported { construct, .. } if construct == "2025"
+    ));
+    assert!(matches!(
+        systemd_error("*-02~03"),
+        ScheduleError::Unsupported { construct, .. } if construct == "*-02~03"
+    ));
+    assert!(matches!(
+        systemd_error("Mon *-*-1..7"),
+        ScheduleError::Unsupported { .. }
+    ));
+    assert!(matches!(
+        systemd_error("daily UTC"),
+        ScheduleError::Unsupported { construct, .. } if construct == "UTC"
+    ));
+    assert!(matches!(
+        systemd_error("Mon 09:00 Europe/Berlin"),
+        ScheduleError::Unsupported { construct, .. } if construct == "Europe/Berlin"
+    ));
+    assert!(matches!(
+        systemd_error("Mon 09:00 junk"),
+        ScheduleError::InvalidField {
+            field: "calendar event",
+            ..
+        }
+    ));
+    assert_eq!(
+        systemd_error("*-*-* 24:00"),
+        ScheduleError::OutOfRange {
+            field: "hour",
+            value: 24,
+            min: 0,
+            max: 23
+        }
+    );
+    assert!(matches!(
+        systemd_error("Funday 09:00"),
+        ScheduleError::InvalidField {
+            field: "weekday",
+            ..
+        }
+    ));
+    assert!(matches!(
+        systemd_error(""),
+        ScheduleError::InvalidField { .. }
+    ));
+}
+
+fn rrule_error(rule: &str) -> ScheduleError {
+    match CalendarInterval::from_rrule(rule) {
+        Err(crate::Error::Schedule { source, .. }) => source,
+        other => panic!("expected a schedule error, got {other:?}"),
+    }
+}
+
+#[test]
+fn from_rrule_matches_equivalent_cron() {
+    for (rule, cron) in [
+        (
+            "FREQ=WEEKLY;BYDAY=MO,WE;BYHOUR=9;BYMINUTE=30",
+            "30 9 * * 1,3",
+        ),
+        ("RRULE:FREQ=DAILY;BYHOUR=2", "0 2 * * *"),
+        ("FREQ=MINUTELY;INTERVAL=15;BYHOUR=9,10", "*/15 9,10 * * *"),
+        ("FREQ=HOURLY;INTERVAL=6", "0 */6 * * *"),
+        ("FREQ=MONTHLY;INTERVAL=3;BYMONTHDAY=1", "0 0 1 */3 *"),
+        (
+            "FREQ=YEARLY;BYMONTH=12;BYMONTHDAY=25;BYSECOND=0",
+            "0 0 25 12 *",
+        ),
+    ] {
+        assert_eq!(
+            CalendarInterval::from_rrule(rule).unwrap(),
+            CalendarInterval::from_cron(cron).unwrap(),
+            "{rule}"
+        );
+    }
+}
+
+#[test]
+fn from_rrule_rejects_what_launchd_cannot_express() {
+    for rule in [
+        "FREQ=DAILY;COUNT=10",
+        "FREQ=DAILY;UNTIL=20250101T000000Z",
+        "FREQ=MINUTELY;INTERVAL=7",
+        "FREQ=DAILY;INTERVAL=2",
+        "FREQ=MONTHLY;BYDAY=1MO",
+        "FREQ=MONTHLY;BYMONTHDAY=-1",
+        "FREQ=MONTHLY;BYMONTHDAY=1;BYDAY=MO",
+        "FREQ=WEEKLY",
+        "FREQ=WEEKLY;BYMONTHDAY=1",
+        "FREQ=SECONDLY",
+        "FREQ=DAILY;BYSECOND=30",
+    ] {
+        assert!(
+            matches!(rrule_error(rule), ScheduleError::Unsupported { .. }),
+            "{rule}"
+        );
+    }
+
+    assert!(matches!(
+        rrule_error("BYHOUR=9"),
+        ScheduleError::InvalidField { .. }
+    ));
+    assert_eq!(
+        rrule_error("FREQ=DAILY;BYHOUR=25"),
+        ScheduleError::OutOfRange {
+            field: "hour",
+            value: 25,
+            min: 0,
+            max: 23
+        }
+    );
+}
+
+#[test]
+fn to_rrule_round_trips_and_splits_day_or_weekday() {
+    let rule = "FREQ=WEEKLY;BYDAY=MO,WE;BYHOUR=9;BYMINUTE=30";
+    let intervals = CalendarInterval::from_rrule(rule).unwrap();
+    assert_eq!(
+        CalendarInterval::to_rrule(&intervals),
+        Some(vec![rule.to_string()])
+    );
+
+    let either = [interval(
+        CalendarIntervalBuilder::default()
+            .minute(0u32)
+            .unwrap()
+            .hour(6u32)
+            .unwrap()
+            .day(1u32)
+            .unwrap()
+            .weekday(5u8)
+            .unwrap(),
+    )];
+    assert_eq!(
+        CalendarInterval::to_rrule(&either),
+        Some(vec![
+            "FREQ=MONTHLY;BYMONTHDAY=1;BYHOUR=6;BYMINUTE=0".to_string(),
+            "FREQ=WEEKLY;BYDAY=FR;BYHOUR=6;BYMINUTE=0".to_string(),
+        ])
+    );
+    assert_eq!(
+        CalendarInterval::to_rrule(&[CalendarInterval::default()]),
+        Some(vec!["FREQ=MINUTELY".to_string()])
+    );
+}
+
+fn human(text: &str) -> Vec<CalendarInterval> {
+    CalendarInterval::parse_human(text).unwrap()
+}
+
+#[test]
+fn parse_human_matches_equivalent_cron() {
+    for (text, cron) in [
+        ("every weekday at 9:30 and 17:00", "30 9 * * 1-5"),
+        ("hourly", "0 * * * *"),
+        ("every hour at :15 and :45", "15,45 * * * *"),
+        ("15 minutes past the hour on weekends", "15 * * * 0,6"),
+        ("daily at 6am", "0 6 * * *"),
+        ("Every Monday and Friday at 5:30 pm", "30 17 * * 1,5"),
+        ("on the first of the month at noon", "0 12 1 * *"),
+        ("monthly", "@monthly"),
+        (
+            "the 1st and 15th of January and July at midnight",
+            "0 0 1,15 1,7 *",
+        ),
+        ("every day in March at 12am", "0 0 * 3 *"),
+        ("every minute", "* * * * *"),
+        ("every 15 minutes", "*/15 * * * *"),
+        ("every 2 hours", "0 */2 * * *"),
+        ("every 6 hours at :30 on weekdays", "30 */6 * * 1-5"),
+        ("every minute during the 9:00 hour", "* 9 * * *"),
+    ] {
+        let mut expected = CalendarInterval::from_cron(cron).unwrap();
+        if text.starts_with("every weekday") {
+            expected.extend(CalendarInterval::from_cron("0 17 * * 1-5").unwrap());
+        }
+        assert_eq!(
+            CalendarInterval::compress(&human(text)),
+            CalendarInterval::compress(&expected),
+            "{text}"
+        );
+    }
+}
+
+#[test]
+fn parse_human_rejects_what_it_cannot_understand() {
+    for text in [
+        "every fortnight",
+        "at 25:00",
+        "at 13pm",
+        "every 0 minutes",
+        "every 90 minutes",
+        "every second",
+        "at 9:00 every third",
+    ] {
+        assert!(CalendarInterval::parse_human(text).is_err(), "{text}");
+    }
+
+    for text in [
+        "the last day of the month",
+        "the first monday of the month",
+        "every monday on the 1st",
+        "hourly at 9:00",
+        "every 2 hours at 9:00",
+        "every minute during the 9:30 hour",
+    ] {
+        assert!(
+            matches!(
+                CalendarInterval::parse_human(text),
+                Err(crate::Error::Schedule {
+                    source: ScheduleError::Unsupported { .. },
+                    ..
+                })
+            ),
+            "{text}"
+        );
+    }
+}
+
+/// The first runs of a set of intervals in 2024, for comparing schedules that
+/// `launchd` runs the same way but are written differently.
+fn first_runs(intervals: &[CalendarInterval]) -> Vec<DateTime> {
+    let mut time = at("2024-01-01 00:00");
+    std::iter::from_fn(|| {
+        time = intervals
+            .iter()
+            .filter_map(|interval| interval.next_after(time))
+            .min()?;
+        Some(time)
+    })
+    .take(500)
+    .collect()
+}
+
+#[test]
+fn describe_reads_back_through_parse_human() {
+    for (text, description) in [
+        (
+            "every weekday at 9:30 and 17:00",
+            "Every weekday at 9:30 and 17:00.",
+        ),
+        (
+            "every hour at :15 on weekends",
+            "Every hour at :15 every weekend.",
+        ),
+        (
+            "on the 1st and 15th of every month at 6:00",
+            "On the 1st and 15th of every month at 6:00.",
+        ),
+        (
+            "every tuesday in june at noon",
+            "Every Tuesday in June at 12:00.",
+        ),
+        ("every minute", "Every minute."),
+        (
+            "daily at 2am; the 1st of january at 3am",
+            "Every day at 2:00; on the 1st of January at 3:00.",
+        ),
+    ] {
+        let intervals = human(text);
+        assert_eq!(CalendarInterval::describe(&intervals), description);
+        assert_eq!(human(description), intervals, "{description}");
+    }
+
+    for (cron, description) in [
+        (
+            "0 9 1 * 1",
+            "On the 1st of every month at 9:00; every Monday at 9:00.",
+        ),
+        ("* 9 * * *", "Every minute during the 9:00 hour."),
+        (
+            "* 9,17 * * 6",
+            "Every minute during the 9:00 and 17:00 hours every Saturday.",
+        ),
+    ] {
+        let intervals = CalendarInterval::from_cron(cron).unwrap();
+        assert_eq!(
+            CalendarInterval::describe(&intervals),
+            description,
+            "{cron}"
+        );
+        assert_eq!(
+            first_runs(&human(description)),
+            first_runs(&intervals),
+            "{description}"
+        );
+    }
+
+    assert_eq!(CalendarInterval::describe(&[]), "Never.");
+    assert_eq!(human("Never."), []);
+
+    // Intervals loaded with out-of-range fields never run.
+    let mut intervals = CalendarInterval::from_cron("0 9 * * 1").unwrap();
+    let out_of_range: CalendarInterval =
+        plist::from_bytes(br#"{ Weekday = 9; Hour = 9; }"#).unwrap();
+    assert_eq!(
+        CalendarInterval::describe(std::slice::from_ref(&out_of_range)),
+        "Never."
+    );
+    intervals.push(out_of_range);
+    assert_eq!(
+        CalendarInterval::describe(&intervals),
+        "Every Monday at 9:00."
+    );
+}
+
+#[test]
+fn splay_is_stable_and_keyed() {
+    let nightly = interval(
+        CalendarIntervalBuilder::default()
+            .hour(2u32)
+            .unwrap()
+            .minute(0u32)
+            .unwrap(),
+    );
+    let window = std::time::Duration::from_secs(60 * 60);
+
+    let first = nightly.splay(window, "com.example.backup");
+    assert_eq!(first, nightly.splay(window, "com.example.backup"));
+    assert_eq!(first.hour(), Some(2));
+
+    let minutes: std::collections::BTreeSet<_> = (0..20)
+        .map(|host| nightly.splay(window, &format!("host-{host}")).minute())
+        .collect();
+    assert!(minutes.len() > 10, "{minutes:?}");
+
+    assert_eq!(
+        nightly.splay(std::time::Duration::from_secs(59), "any"),
+        nightly
+    );
+}
+
+#[test]
+fn splay_keeps_the_original_constraints() {
+    let window = std::time::Duration::from_secs(24 * 60 * 60);
+    let late = interval(
+        CalendarIntervalBuilder::default()
+            .hour(23u32)
+            .unwrap()
+            .minute(50u32)
+            .unwrap()
+            .weekday(Weekday::Friday)
+            .unwrap()
+            .month(Month::March)
+            .unwrap(),
+    );
+    let hourly = interval(CalendarIntervalBuilder::default().minute(30u32).unwrap());
+
+    for host in 0..50 {
+        let key = format!("host-{host}");
+
+        let splayed = late.splay(window, &key);
+        assert_eq!(splayed.hour(), Some(23));
+        assert!(splayed.minute().unwrap() >= 50);
+        assert_eq!(splayed.weekday().unwrap(), Some(Weekday::Friday));
+        assert_eq!(splayed.month().unwrap(), Some(Month::March));
+
+        let splayed = hourly.splay(window, &key);
+        assert_eq!(splayed.hour(), None);
+        assert!(splayed.minute().unwrap() < 60);
+    }
+
+    assert_eq!(
+        CalendarInterval::default().splay(window, "any"),
+        CalendarInterval::default()
+    );
+}
+
+#[test]
+fn splay_schedule_shifts_every_interval_by_the_same_amount() {
+    let mut agent = LaunchAgentBuilder::default()
+        .label("com.example.backup")
+        .program("/usr/bin/backup")
+        .start_calendar_interval(vec![
+            interval(
+                CalendarIntervalBuilder::default()
+                    .hour(2u32)
+                    .unwrap()
+                    .minute(0u32)
+                    .unwrap(),
+            ),
+            interval(
+                CalendarIntervalBuilder::default()
+                    .hour(14u32)
+                    .unwrap()
+                    .minute(0u32)
+                    .unwrap(),
+            ),
+        ])
+        .build()
+        .unwrap();
+    let mut other_host = agent.clone();
+
+    agent.splay_schedule(std::time::Duration::from_secs(30 * 60), Some("C02ABC123"));
+    other_host.splay_schedule(std::time::Duration::from_secs(30 * 60), Some("C02XYZ789"));
+
+    let intervals = agent.start_calendar_interval.as_ref().unwrap();
+    assert_eq!(intervals[0].minute(), intervals[1].minute());
+    assert!(intervals[0].minute().unwrap() < 30);
+    assert_ne!(agent, other_host);
+}
+
+fn scheduled(label: &str, cron: &str) -> crate::LaunchAgent {
+    LaunchAgentBuilder::default()
+        .label(label)
+        .program("/usr/bin/example")
+        .start_calendar_interval(CalendarInterval::from_cron(cron).unwrap())
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn load_report_finds_collisions_and_fills_the_heatmap() {
+    let agents = [
+        scheduled("com.example.a", "0 * * * *"),
+        scheduled("com.example.b", "0 2 * * *"),
+        scheduled("com.example.c", "0 2 * * 1"),
+        scheduled("com.example.d", "30 3 * * *"),
+    ];
+
+    // 2024-01-01 is a Monday.
+    let report = LoadReport::new(&agents, at("2024-01-01 00:00"), at("2024-01-08 00:00"));
+
+    // Runs start strictly after load time, so midnight on Monday is skipped.
+    assert_eq!(report.runs(), 7 * 24 - 1 + 7 + 1 + 7);
+    assert_eq!(report.heatmap()[1][2], 3);
+    assert_eq!(report.heatmap()[2][2], 2);
+    assert_eq!(report.heatmap()[0][3], 2);
+    assert_eq!(report.max_concurrency(), 3);
+
+    let collisions = report.collisions();
+    assert_eq!(collisions.len(), 7);
+    assert_eq!(
+        collisions[0],
+        Collision {
+            weekday: Weekday::Monday,
+            hour: 2,
+            minute: 0,
+            labels: vec![
+                "com.example.a".into(),
+                "com.example.b".into(),
+                "com.example.c".into()
+            ],
+            occurrences: 1,
+            first: at("2024-01-01 02:00"),
+        }
+    );
+    assert!(
+        collisions[1..]
+            .iter()
+            .all(|collision| collision.labels.len() == 2)
+    );
+
+    let text = report.to_string();
+    assert!(
+        text.contains("Mon 02:00  3 agents, 1 times: com.example.a, com.example.b, com.example.c")
+    );
+}
+
+#[test]
+fn load_report_counts_interval_ticks_from_load_time() {
+    let mut ticking = scheduled("com.example.tick", "0 0 1 1 *");
+    ticking.start_interval = Some(crate::Interval::from(30 * 60));
+    let agents = [ticking, scheduled("com.example.hourly", "0 * * * *")];
+
+    let report = LoadReport::new(&agents, at("2024-01-02 00:00"), at("2024-01-02 06:00"));
+
+    assert_eq!(report.runs(), 11 + 5);
+    assert_eq!(report.collisions().len(), 5);
+    assert!(
+        report
+            .collisions()
+            .iter()
+            .all(|collision| collision.minute == 0)
+    );
+
+    let quiet = LoadReport::new(&agents[1..], at("2024-01-02 00:00"), at("2024-01-02 06:00"));
+    assert_eq!(quiet.max_concurrency(), 1);
+    assert!(quiet.to_string().ends_with("No collisions.\n"));
+
+    let idle = LoadReport::new(&[], at("2024-01-02 00:00"), at("2024-01-02 06:00"));
+    assert_eq!(idle.runs(), 0);
+    assert_eq!(idle.max_concurrency(), 0);
+}
+
+#[test]
+fn load_report_counts_agents_sharing_a_label() {
+    let agents = [
+        scheduled("com.example.backup", "0 2 * * *"),
+        scheduled("com.example.backup", "0 2 * * *"),
+    ];
+
+    let report = LoadReport::new(&agents, at("2024-01-01 00:00"), at("2024-01-02 00:00"));
+
+    assert_eq!(report.max_concurrency(), 2);
+    assert_eq!(
+        report.collisions()[0].labels,
+        ["com.example.backup", "com.example.backup"]
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn load_report_serializes_to_json() {
+    let agents = [
+        scheduled("com.example.a", "0 2 * * *"),
+        scheduled("com.example.b", "0 2 * * *"),
+    ];
+    let report = LoadReport::new(&agents, at("2024-01-01 00:00"), at("2024-01-02 00:00"));
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+
+    assert_eq!(json["runs"], 2);
+    assert_eq!(json["collisions"][0]["weekday"], "Monday");
+    assert_eq!(json["collisions"][0]["first"], "2024-01-01 02:00:00");
+    assert_eq!(json["heatmap"][1][2], 2);
+}
@@ -1,26 +1,197 @@
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
-#[derive(Builder, Clone, Deserialize, Serialize)]
+use crate::{
+    error::Error,
+    schedule::pattern::Field,
+    validation::{ValidationError, ValidationIssue},
+};
+
+mod dates;
+
+#[cfg(test)]
+mod tests;
+
+pub use dates::{Month, Weekday};
+
+#[derive(Builder, Clone, Debug, Default, Deserialize, PartialEq, Eq, Hash, Serialize)]
 #[serde(rename_all = "PascalCase")]
-#[builder(setter(into, strip_option))]
+#[builder(default, build_fn(error = "Error"))]
 pub struct CalendarInterval {
     /// The minute (0-59) on which this job will be run.
-    minute: Option<u32>,
+    #[builder(setter(custom))]
+    pub(crate) minute: Option<u32>,
 
     /// The hour (0-23) on which this job will be run.
-    hour: Option<u32>,
+    #[builder(setter(custom))]
+    pub(crate) hour: Option<u32>,
 
     /// The day of the month (1-31) on which this job will be run.
-    day: Option<u32>,
+    #[builder(setter(custom))]
+    pub(crate) day: Option<u32>,
 
     /// The weekday on which this job will be run (0 and 7 are Sunday).
     ///
     /// If both [`day`](Self::day) and [`weekday`](Self::weekday) are
     /// specificed, then the job will be started if either one matches the
     /// current date.
-    weekday: Option<u8>,
+    ///
+    /// The builder accepts either a number or a [`Weekday`].
+    #[builder(setter(custom))]
+    pub(crate) weekday: Option<u8>,
 
     /// The month (1-12) on which this job will be run.
-    month: Option<u8>,
+    ///
+    /// The builder accepts either a number or a [`Month`].
+    #[builder(setter(custom))]
+    pub(crate) month: Option<u8>,
+}
+
+impl CalendarInterval {
+    /// The minute (0-59) on which this job will be run, or `None` for every
+    /// minute.
+    pub fn minute(&self) -> Option<u32> {
+        self.minute
+    }
+
+    /// The hour (0-23) on which this job will be run, or `None` for every
+    /// hour.
+    pub fn hour(&self) -> Option<u32> {
+        self.hour
+    }
+
+    /// The day of the month (1-31) on which this job will be run, or `None`
+    /// for every day.
+    pub fn day(&self) -> Option<u32> {
+        self.day
+    }
+
+    /// The weekday on which this job will be run, or `None` for every
+    /// weekday.
+    ///
+    /// Fails if the interval was loaded with a weekday outside of 0 to 7.
+    pub fn weekday(&self) -> Result<Option<Weekday>, Error> {
+        self.weekday.map(Weekday::try_from).transpose()
+    }
+
+    /// The month on which this job will be run, or `None` for every month.
+    ///
+    /// Fails if the interval was loaded with a month outside of 1 to 12.
+    pub fn month(&self) -> Result<Option<Month>, Error> {
+        self.month.map(Month::try_from).transpose()
+    }
+
+    /// Runs at `hour:minute` on each of `weekdays`, or every day if
+    /// `weekdays` is empty.
+    pub fn at(
+        hour: u32,
+        minute: u32,
+        weekdays: impl IntoIterator<Item = impl Into<Weekday>>,
+    ) -> Result<Vec<Self>, Error> {
+        let interval = CalendarIntervalBuilder::default()
+            .hour(hour)?
+            .minute(minute)?
+            .build()?;
+
+        let mut weekdays: Vec<Weekday> = weekdays.into_iter().map(Into::into).collect();
+        weekdays.sort();
+        weekdays.dedup();
+
+        if weekdays.is_empty() {
+            return Ok(vec![interval]);
+        }
+
+        Ok(weekdays
+            .into_iter()
+            .map(|weekday| Self {
+                weekday: Some(weekday.number()),
+                ..interval.clone()
+            })
+            .collect())
+    }
+
+    /// Describes why the interval's day can never match, such as February 30,
+    /// or `None` if it can.
+    ///
+    /// When [`weekday`](Self::weekday) is also set, the job still runs on
+    /// that weekday, but the day itself is dead.
+    pub fn impossible_date(&self) -> Option<String> {
+        let day = self.day?;
+        let month = self.month().ok().flatten()?;
+
+        (day > u32::from(month.max_days())).then(|| format!("{month} {day} does not exist"))
+    }
+
+    /// Describes each field that lies outside of the range `launchd`
+    /// accepts.
+    pub(crate) fn out_of_range(&self) -> Vec<ValidationIssue> {
+        out_of_range([
+            (Field::MINUTE, self.minute),
+            (Field::HOUR, self.hour),
+            (Field::DAY, self.day),
+            (Field::WEEKDAY, self.weekday.map(u32::from)),
+            (Field::MONTH, self.month.map(u32::from)),
+        ])
+    }
+}
+
+impl CalendarIntervalBuilder {
+    /// Sets the minute, failing if it is not between 0 and 59.
+    pub fn minute(&mut self, minute: impl Into<u32>) -> Result<&mut Self, Error> {
+        self.minute = Some(Some(checked(Field::MINUTE, minute.into())?));
+        Ok(self)
+    }
+
+    /// Sets the hour, failing if it is not between 0 and 23.
+    pub fn hour(&mut self, hour: impl Into<u32>) -> Result<&mut Self, Error> {
+        self.hour = Some(Some(checked(Field::HOUR, hour.into())?));
+        Ok(self)
+    }
+
+    /// Sets the day of the month, failing if it is not between 1 and 31.
+    pub fn day(&mut self, day: impl Into<u32>) -> Result<&mut Self, Error> {
+        self.day = Some(Some(checked(Field::DAY, day.into())?));
+        Ok(self)
+    }
+
+    /// Sets the weekday, failing if it is not between 0 and 7.
+    pub fn weekday(&mut self, weekday: impl Into<u8>) -> Result<&mut Self, Error> {
+        let weekday = weekday.into();
+        checked(Field::WEEKDAY, weekday.into())?;
+        self.weekday = Some(Some(weekday));
+        Ok(self)
+    }
+
+    /// Sets the month, failing if it is not between 1 and 12.
+    pub fn month(&mut self, month: impl Into<u8>) -> Result<&mut Self, Error> {
+        let month = month.into();
+        checked(Field::MONTH, month.into())?;
+        self.month = Some(Some(month));
+        Ok(self)
+    }
+}
+
+/// An issue for each value that lies outside of its field's range.
+fn out_of_range(fields: impl IntoIterator<Item = (Field, Option<u32>)>) -> Vec<ValidationIssue> {
+    fields
+        .into_iter()
+        .filter_map(|(field, value)| {
+            let value = value?;
+            field
+                .check(value)
+                .err()
+                .map(|_| ValidationIssue::CalendarFieldOutOfRange {
+                    field: field.name,
+                    value,
+                    min: field.min,
+                    max: field.max,
+                })
+        })
+        .collect()
+}
+
+/// Returns `value` if it lies within `field`'s range.
+fn checked(field: Field, value: u32) -> Result<u32, Error> {
+    ValidationError::from_issues(out_of_range([(field, Some(value))]))
+        .map_or(Ok(value), |error| Err(error.into()))
 }
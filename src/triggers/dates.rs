@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+use crate::error::Error;
+
+/// A day of the week, numbered from Sunday as `launchd` does.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Weekday {
+    Sunday = 0,
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+}
+
+impl Weekday {
+    /// Every weekday, starting from Sunday.
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Sunday,
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+    ];
+
+    /// The number `launchd` uses for the weekday, from 0 (Sunday) to 6.
+    pub fn number(self) -> u8 {
+        self as u8
+    }
+
+    /// The English name of the weekday, such as `Monday`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sunday => "Sunday",
+            Self::Monday => "Monday",
+            Self::Tuesday => "Tuesday",
+            Self::Wednesday => "Wednesday",
+            Self::Thursday => "Thursday",
+            Self::Friday => "Friday",
+            Self::Saturday => "Saturday",
+        }
+    }
+}
+
+impl From<Weekday> for u8 {
+    fn from(weekday: Weekday) -> Self {
+        weekday.number()
+    }
+}
+
+impl TryFrom<u8> for Weekday {
+    type Error = Error;
+
+    /// Accepts 0 to 7, where both 0 and 7 are Sunday.
+    fn try_from(number: u8) -> Result<Self, Self::Error> {
+        Self::ALL
+            .get(usize::from(number % 7))
+            .copied()
+            .filter(|_| number <= 7)
+            .ok_or_else(|| Error::InvalidValue {
+                value: number.to_string(),
+                expected: "a weekday from 0 to 7",
+            })
+    }
+}
+
+impl fmt::Display for Weekday {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Weekday {
+    type Err = Error;
+
+    /// Parses a full or three-letter English name, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|weekday| matches_name(s, weekday.name()))
+            .ok_or_else(|| Error::InvalidValue {
+                value: s.to_string(),
+                expected: "a weekday name such as Monday or Mon",
+            })
+    }
+}
+
+/// A month of the year, numbered from 1.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Month {
+    January = 1,
+    February,
+    March,
+    April,
+    May,
+    June,
+    July,
+    August,
+    September,
+    October,
+    November,
+    December,
+}
+
+impl Month {
+    /// Every month, starting from January.
+    pub const ALL: [Month; 12] = [
+        Month::January,
+        Month::February,
+        Month::March,
+        Month::April,
+        Month::May,
+        Month::June,
+        Month::July,
+        Month::August,
+        Month::September,
+        Month::October,
+        Month::November,
+        Month::December,
+    ];
+
+    /// The number of the month, from 1 (January) to 12.
+    pub fn number(self) -> u8 {
+        self as u8
+    }
+
+    /// The English name of the month, such as `January`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::January => "January",
+            Self::February => "February",
+            Self::March => "March",
+            Self::April => "April",
+            Self::May => "May",
+            Self::June => "June",
+            Self::July => "July",
+            Self::August => "August",
+            Self::September => "September",
+            Self::October => "October",
+            Self::November => "November",
+            Self::December => "December",
+        }
+    }
+
+    /// The most days the month can have, counting February 29.
+    pub fn max_days(self) -> u8 {
+        match self {
+            Self::February => 29,
+            Self::April | Self::June | Self::September | Self::November => 30,
+            _ => 31,
+        }
+    }
+}
+
+impl From<Month> for u8 {
+    fn from(month: Month) -> Self {
+        month.number()
+    }
+}
+
+impl TryFrom<u8> for Month {
+    type Error = Error;
+
+    fn try_from(number: u8) -> Result<Self, Self::Error> {
+        number
+            .checked_sub(1)
+            .and_then(|index| Self::ALL.get(usize::from(index)))
+            .copied()
+            .ok_or_else(|| Error::InvalidValue {
+                value: number.to_string(),
+                expected: "a month from 1 to 12",
+            })
+    }
+}
+
+impl fmt::Display for Month {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Month {
+    type Err = Error;
+
+    /// Parses a full or three-letter English name, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|month| matches_name(s, month.name()))
+            .ok_or_else(|| Error::InvalidValue {
+                value: s.to_string(),
+                expected: "a month name such as January or Jan",
+            })
+    }
+}
+
+fn matches_name(s: &str, name: &str) -> bool {
+    s.eq_ignore_ascii_case(name) || s.eq_ignore_ascii_case(&name[..3])
+}
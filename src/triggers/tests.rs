@@ -0,0 +1,153 @@
+use super::*;
+
+#[test]
+fn setters_reject_out_of_range_fields() {
+    assert!(
+        CalendarIntervalBuilder::default()
+            .minute(59u32)
+            .unwrap()
+            .build()
+            .is_ok()
+    );
+    assert!(
+        CalendarIntervalBuilder::default()
+            .weekday(7u8)
+            .unwrap()
+            .build()
+            .is_ok()
+    );
+
+    let mut builder = CalendarIntervalBuilder::default();
+    for result in [
+        builder.minute(75u32).map(|_| ()),
+        builder.hour(24u32).map(|_| ()),
+        builder.day(0u32).map(|_| ()),
+        builder.weekday(8u8).map(|_| ()),
+        builder.month(13u8).map(|_| ()),
+    ] {
+        assert!(
+            matches!(
+                &result,
+                Err(Error::Validation(error))
+                    if matches!(error.issues(), [ValidationIssue::CalendarFieldOutOfRange { .. }])
+            ),
+            "{result:?}"
+        );
+    }
+    assert_eq!(builder.build().unwrap(), CalendarInterval::default());
+}
+
+#[test]
+fn every_out_of_range_field_is_reported() {
+    let interval = CalendarInterval {
+        minute: Some(75),
+        hour: Some(9),
+        day: Some(0),
+        weekday: None,
+        month: Some(13),
+    };
+
+    let fields: Vec<&str> = interval
+        .out_of_range()
+        .iter()
+        .map(|issue| match issue {
+            ValidationIssue::CalendarFieldOutOfRange { field, .. } => *field,
+            issue => panic!("unexpected issue {issue:?}"),
+        })
+        .collect();
+    assert_eq!(fields, ["minute", "day", "month"]);
+    assert!(interval.month().is_err());
+    assert_eq!(interval.weekday().unwrap(), None);
+    assert_eq!(
+        interval.out_of_range()[0].to_string(),
+        "calendar interval minute must be between 0 and 59, got 75"
+    );
+}
+
+#[test]
+fn builder_accepts_typed_weekdays_and_months() {
+    let interval = CalendarIntervalBuilder::default()
+        .weekday(Weekday::Friday)
+        .unwrap()
+        .month(Month::March)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(interval.weekday().unwrap(), Some(Weekday::Friday));
+    assert_eq!(interval.month().unwrap(), Some(Month::March));
+    assert_eq!(interval.minute(), None);
+}
+
+#[test]
+fn weekday_seven_is_sunday() {
+    let interval = CalendarIntervalBuilder::default()
+        .weekday(7u8)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(interval.weekday().unwrap(), Some(Weekday::Sunday));
+    assert!(Weekday::try_from(8).is_err());
+    assert!(Month::try_from(0).is_err());
+}
+
+#[test]
+fn weekdays_and_months_parse_names() {
+    assert_eq!("mon".parse::<Weekday>().unwrap(), Weekday::Monday);
+    assert_eq!("Saturday".parse::<Weekday>().unwrap(), Weekday::Saturday);
+    assert_eq!("SEP".parse::<Month>().unwrap(), Month::September);
+    assert!("Mo".parse::<Weekday>().is_err());
+}
+
+#[test]
+fn weekdays_and_months_round_trip_through_serde() {
+    let weekday = plist::to_value(&Weekday::Friday).unwrap();
+    let month = plist::to_value(&Month::March).unwrap();
+
+    assert_eq!(
+        plist::from_value::<Weekday>(&weekday).unwrap(),
+        Weekday::Friday
+    );
+    assert_eq!(plist::from_value::<Month>(&month).unwrap(), Month::March);
+}
+
+#[test]
+fn at_builds_one_interval_per_weekday() {
+    let intervals = CalendarInterval::at(9, 30, [Weekday::Wednesday, Weekday::Monday]).unwrap();
+
+    assert_eq!(
+        intervals
+            .iter()
+            .map(|interval| interval.weekday().unwrap())
+            .collect::<Vec<_>>(),
+        [Some(Weekday::Monday), Some(Weekday::Wednesday)]
+    );
+    assert!(intervals.iter().all(|interval| interval.hour() == Some(9)));
+    assert_eq!(
+        CalendarInterval::at(9, 30, [] as [Weekday; 0])
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(CalendarInterval::at(9, 60, [Weekday::Monday]).is_err());
+}
+
+#[test]
+fn impossible_dates_are_described() {
+    let feb = |day: u32| {
+        CalendarIntervalBuilder::default()
+            .month(Month::February)
+            .unwrap()
+            .day(day)
+            .unwrap()
+            .build()
+            .unwrap()
+    };
+
+    assert_eq!(feb(29).impossible_date(), None);
+    assert_eq!(
+        feb(30).impossible_date().as_deref(),
+        Some("February 30 does not exist")
+    );
+}
@@ -0,0 +1,455 @@
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, Visitor},
+};
+use std::{fmt, str::FromStr, time::Duration};
+
+use crate::error::Error;
+
+#[cfg(test)]
+mod tests;
+
+/// The largest value a property list integer can hold as a signed value,
+/// which is how `launchd` reads it.
+const MAX_PLIST_INTEGER: u64 = i64::MAX as u64;
+
+/// A size in bytes, such as a resource limit.
+///
+/// A `ByteSize` is written to property lists as a plain integer, but can be
+/// parsed from a human-readable string such as `512MiB` or `2G`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(u64);
+
+impl ByteSize {
+    pub const KIB: u64 = 1 << 10;
+    pub const MIB: u64 = 1 << 20;
+    pub const GIB: u64 = 1 << 30;
+    pub const TIB: u64 = 1 << 40;
+
+    /// Creates a size from a number of bytes, failing if it does not fit in
+    /// a property list integer.
+    pub fn new(bytes: u64) -> Result<Self, Error> {
+        if bytes > MAX_PLIST_INTEGER {
+            return Err(Error::InvalidValue {
+                value: bytes.to_string(),
+                expected: "a size that fits in a property list integer",
+            });
+        }
+
+        Ok(Self(bytes))
+    }
+
+    /// The number of bytes.
+    pub const fn bytes(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u32> for ByteSize {
+    fn from(bytes: u32) -> Self {
+        Self(bytes.into())
+    }
+}
+
+impl TryFrom<u64> for ByteSize {
+    type Error = Error;
+
+    fn try_from(bytes: u64) -> Result<Self, Self::Error> {
+        Self::new(bytes)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(size: ByteSize) -> Self {
+        size.0
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = Error;
+
+    /// Parses a whole number of bytes with an optional unit.
+    ///
+    /// `K`, `M`, `G` and `T` and their `KiB`-style spellings are powers of
+    /// 1024, as in `ulimit(1)`. `KB`, `MB`, `GB` and `TB` are powers of 1000.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidValue {
+            value: s.to_string(),
+            expected: "a size such as 4096, 512MiB or 2G",
+        };
+
+        let trimmed = s.trim();
+        let split = trimmed
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(trimmed.len());
+        let (number, unit) = trimmed.split_at(split);
+        let number: u64 = number.parse().map_err(|_| invalid())?;
+
+        let multiplier = match unit.trim().to_ascii_lowercase().as_str() {
+            "" | "b" => 1,
+            "k" | "kib" => Self::KIB,
+            "m" | "mib" => Self::MIB,
+            "g" | "gib" => Self::GIB,
+            "t" | "tib" => Self::TIB,
+            "kb" => 1_000,
+            "mb" => 1_000_000,
+            "gb" => 1_000_000_000,
+            "tb" => 1_000_000_000_000,
+            _ => return Err(invalid()),
+        };
+
+        number
+            .checked_mul(multiplier)
+            .filter(|bytes| *bytes <= MAX_PLIST_INTEGER)
+            .map(Self)
+            .ok_or_else(invalid)
+    }
+}
+
+impl TryFrom<&str> for ByteSize {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for ByteSize {
+    /// Displays the size in the largest binary unit that divides it evenly.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let units = [
+            (Self::TIB, "TiB"),
+            (Self::GIB, "GiB"),
+            (Self::MIB, "MiB"),
+            (Self::KIB, "KiB"),
+        ];
+
+        match units
+            .into_iter()
+            .find(|(size, _)| self.0 != 0 && self.0.is_multiple_of(*size))
+        {
+            Some((size, unit)) => write!(f, "{}{unit}", self.0 / size),
+            None => write!(f, "{}B", self.0),
+        }
+    }
+}
+
+impl Serialize for ByteSize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteSize {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_integer(deserializer, "a size in bytes", Self::new)
+    }
+}
+
+/// A whole number of seconds, such as a CPU time limit.
+///
+/// `Seconds` are written to property lists as a plain integer, but can be
+/// created from a [`Duration`] or a human-readable string such as `2h30m`,
+/// and converted back into a [`Duration`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Seconds(u64);
+
+impl Seconds {
+    /// Creates a value from a number of seconds, failing if it does not fit
+    /// in a property list integer.
+    pub fn new(seconds: u64) -> Result<Self, Error> {
+        if seconds > MAX_PLIST_INTEGER {
+            return Err(Error::InvalidValue {
+                value: seconds.to_string(),
+                expected: "a number of seconds that fits in a property list integer",
+            });
+        }
+
+        Ok(Self(seconds))
+    }
+
+    /// The number of seconds.
+    pub const fn get(&self) -> u64 {
+        self.0
+    }
+
+    /// The value as a [`Duration`].
+    pub const fn as_duration(&self) -> Duration {
+        Duration::from_secs(self.0)
+    }
+}
+
+impl From<u32> for Seconds {
+    fn from(seconds: u32) -> Self {
+        Self(seconds.into())
+    }
+}
+
+impl TryFrom<u64> for Seconds {
+    type Error = Error;
+
+    fn try_from(seconds: u64) -> Result<Self, Self::Error> {
+        Self::new(seconds)
+    }
+}
+
+impl From<Seconds> for u64 {
+    fn from(seconds: Seconds) -> Self {
+        seconds.0
+    }
+}
+
+impl From<Seconds> for Duration {
+    fn from(seconds: Seconds) -> Self {
+        seconds.as_duration()
+    }
+}
+
+impl TryFrom<Duration> for Seconds {
+    type Error = Error;
+
+    /// Fails if `duration` is not a whole number of seconds or does not fit
+    /// in a property list integer.
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        if duration.subsec_nanos() != 0 || duration.as_secs() > MAX_PLIST_INTEGER {
+            return Err(Error::InvalidValue {
+                value: format!("{duration:?}"),
+                expected: "a whole number of seconds",
+            });
+        }
+
+        Ok(Self(duration.as_secs()))
+    }
+}
+
+impl FromStr for Seconds {
+    type Err = Error;
+
+    /// Parses a whole number of seconds, or one or more numbers each followed
+    /// by a unit of `d`, `h`, `m` or `s`, such as `15m`, `2h30m` or `1d`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidValue {
+            value: s.to_string(),
+            expected: "a duration such as 90, 15m, 2h30m or 1d",
+        };
+
+        let trimmed = s.trim();
+        if let Ok(seconds) = trimmed.parse::<u64>() {
+            return Some(seconds)
+                .filter(|seconds| *seconds <= MAX_PLIST_INTEGER)
+                .map(Self)
+                .ok_or_else(invalid);
+        }
+
+        let mut rest = trimmed;
+        let mut total: u64 = 0;
+        while !rest.is_empty() {
+            let split = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .ok_or_else(invalid)?;
+            let (number, tail) = rest.split_at(split);
+            let number: u64 = number.parse().map_err(|_| invalid())?;
+
+            let unit = tail.chars().next().ok_or_else(invalid)?;
+            let multiplier = match unit.to_ascii_lowercase() {
+                'd' => 86_400,
+                'h' => 3_600,
+                'm' => 60,
+                's' => 1,
+                _ => return Err(invalid()),
+            };
+
+            total = number
+                .checked_mul(multiplier)
+                .and_then(|seconds| total.checked_add(seconds))
+                .ok_or_else(invalid)?;
+            rest = &tail[unit.len_utf8()..];
+        }
+
+        Some(total)
+            .filter(|seconds| *seconds <= MAX_PLIST_INTEGER && !trimmed.is_empty())
+            .map(Self)
+            .ok_or_else(invalid)
+    }
+}
+
+impl TryFrom<&str> for Seconds {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Seconds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}s", self.0)
+    }
+}
+
+impl Serialize for Seconds {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Seconds {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_integer(deserializer, "a number of seconds", Self::new)
+    }
+}
+
+/// A whole number of seconds between two events, such as a job's
+/// [`start_interval`](crate::LaunchAgent::start_interval).
+///
+/// `launchd` reads interval keys as 32-bit integers, so an `Interval` holds
+/// at most [`u32::MAX`] seconds, a little over 136 years. Like [`Seconds`], it
+/// can be created from a [`Duration`] or a string such as `15m`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval(u32);
+
+impl Interval {
+    /// Creates an interval from a number of seconds, failing if it does not
+    /// fit in 32 bits.
+    pub fn new(seconds: u64) -> Result<Self, Error> {
+        u32::try_from(seconds)
+            .map(Self)
+            .map_err(|_| Error::InvalidValue {
+                value: seconds.to_string(),
+                expected: "a number of seconds that fits in 32 bits",
+            })
+    }
+
+    /// The number of seconds.
+    pub const fn get(&self) -> u32 {
+        self.0
+    }
+
+    /// The interval as a [`Duration`].
+    pub const fn as_duration(&self) -> Duration {
+        Duration::from_secs(self.0 as u64)
+    }
+}
+
+impl From<u32> for Interval {
+    fn from(seconds: u32) -> Self {
+        Self(seconds)
+    }
+}
+
+impl TryFrom<u64> for Interval {
+    type Error = Error;
+
+    fn try_from(seconds: u64) -> Result<Self, Self::Error> {
+        Self::new(seconds)
+    }
+}
+
+impl TryFrom<Seconds> for Interval {
+    type Error = Error;
+
+    fn try_from(seconds: Seconds) -> Result<Self, Self::Error> {
+        Self::new(seconds.get())
+    }
+}
+
+impl From<Interval> for u32 {
+    fn from(interval: Interval) -> Self {
+        interval.0
+    }
+}
+
+impl From<Interval> for Seconds {
+    fn from(interval: Interval) -> Self {
+        interval.0.into()
+    }
+}
+
+impl From<Interval> for Duration {
+    fn from(interval: Interval) -> Self {
+        interval.as_duration()
+    }
+}
+
+impl TryFrom<Duration> for Interval {
+    type Error = Error;
+
+    /// Fails if `duration` is not a whole number of seconds or does not fit
+    /// in 32 bits.
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        Seconds::try_from(duration)?.try_into()
+    }
+}
+
+impl FromStr for Interval {
+    type Err = Error;
+
+    /// Parses the same durations as [`Seconds`], such as `90`, `15m` or
+    /// `2h30m`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<Seconds>()?.try_into()
+    }
+}
+
+impl TryFrom<&str> for Interval {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}s", self.0)
+    }
+}
+
+impl Serialize for Interval {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Interval {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_integer(
+            deserializer,
+            "a number of seconds that fits in 32 bits",
+            Self::new,
+        )
+    }
+}
+
+/// Deserializes a non-negative property list integer and builds a value from
+/// it with `new`, which enforces the value's range.
+fn deserialize_integer<'de, D: Deserializer<'de>, T>(
+    deserializer: D,
+    expected: &'static str,
+    new: fn(u64) -> Result<T, Error>,
+) -> Result<T, D::Error> {
+    struct IntegerVisitor<T> {
+        expected: &'static str,
+        new: fn(u64) -> Result<T, Error>,
+    }
+
+    impl<T> Visitor<'_> for IntegerVisitor<T> {
+        type Value = T;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str(self.expected)
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<T, E> {
+            (self.new)(value).map_err(|_| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<T, E> {
+            u64::try_from(value)
+                .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+                .and_then(|value| self.visit_u64(value))
+        }
+    }
+
+    deserializer.deserialize_u64(IntegerVisitor { expected, new })
+}
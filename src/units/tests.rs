@@ -0,0 +1,101 @@
+use super::*;
+
+#[test]
+fn byte_size_parses_units() {
+    assert_eq!("4096".parse::<ByteSize>().unwrap().bytes(), 4096);
+    assert_eq!("512MiB".parse::<ByteSize>().unwrap().bytes(), 512 << 20);
+    assert_eq!("2G".parse::<ByteSize>().unwrap().bytes(), 2 << 30);
+    assert_eq!("8 GiB".parse::<ByteSize>().unwrap().bytes(), 8 << 30);
+    assert_eq!("1MB".parse::<ByteSize>().unwrap().bytes(), 1_000_000);
+}
+
+#[test]
+fn byte_size_rejects_invalid_and_oversized_values() {
+    assert!("".parse::<ByteSize>().is_err());
+    assert!("12 parsecs".parse::<ByteSize>().is_err());
+    assert!("1.5G".parse::<ByteSize>().is_err());
+    assert!("9999999999T".parse::<ByteSize>().is_err());
+}
+
+#[test]
+fn byte_size_displays_in_the_largest_even_unit() {
+    assert_eq!(ByteSize::new(6 << 30).unwrap().to_string(), "6GiB");
+    assert_eq!(ByteSize::new(1536).unwrap().to_string(), "1536B");
+}
+
+#[test]
+fn values_above_the_plist_integer_range_are_rejected() {
+    let max = i64::MAX as u64;
+
+    assert_eq!(ByteSize::try_from(max).unwrap().bytes(), max);
+    assert!(ByteSize::new(max + 1).is_err());
+    assert!(ByteSize::try_from(u64::MAX).is_err());
+    assert_eq!(Seconds::try_from(max).unwrap().get(), max);
+    assert!(Seconds::new(u64::MAX).is_err());
+
+    let too_big = plist::Value::Integer(u64::MAX.into());
+    assert!(plist::from_value::<ByteSize>(&too_big).is_err());
+    assert!(plist::from_value::<Seconds>(&too_big).is_err());
+}
+
+#[test]
+fn seconds_require_whole_seconds() {
+    assert_eq!(
+        Seconds::try_from(Duration::from_secs(90)).unwrap(),
+        Seconds::new(90).unwrap()
+    );
+    assert!(Seconds::try_from(Duration::from_millis(1500)).is_err());
+}
+
+#[test]
+fn seconds_parse_unit_suffixes() {
+    assert_eq!("90".parse::<Seconds>().unwrap(), Seconds::new(90).unwrap());
+    assert_eq!(
+        "15m".parse::<Seconds>().unwrap(),
+        Seconds::new(900).unwrap()
+    );
+    assert_eq!(
+        "2h30m".parse::<Seconds>().unwrap(),
+        Seconds::new(9_000).unwrap()
+    );
+    assert_eq!(
+        "1d".parse::<Seconds>().unwrap(),
+        Seconds::new(86_400).unwrap()
+    );
+    assert_eq!(
+        "1m30s".parse::<Seconds>().unwrap(),
+        Seconds::new(90).unwrap()
+    );
+
+    for invalid in [
+        "",
+        "m",
+        "15mm",
+        "15x",
+        "1.5h",
+        "-5m",
+        "9223372036854775808",
+        "999999999999999d",
+    ] {
+        assert!(invalid.parse::<Seconds>().is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn intervals_fit_in_32_bits() {
+    assert_eq!(Interval::new(u32::MAX.into()).unwrap().get(), u32::MAX);
+    assert!(Interval::new(u64::from(u32::MAX) + 1).is_err());
+    assert_eq!("2h30m".parse::<Interval>().unwrap(), Interval::from(9_000));
+    assert!("50000d".parse::<Interval>().is_err());
+    assert!(Interval::try_from(Duration::from_secs(i64::MAX as u64)).is_err());
+    assert!(Interval::try_from(Duration::from_millis(1500)).is_err());
+}
+
+#[test]
+fn values_above_four_gibibytes_round_trip_as_integers() {
+    let size = ByteSize::new(16 << 30).unwrap();
+    let value = plist::to_value(&size).unwrap();
+
+    assert_eq!(value, plist::Value::Integer((16u64 << 30).into()));
+    assert_eq!(plist::from_value::<ByteSize>(&value).unwrap(), size);
+}
@@ -0,0 +1,162 @@
+use derive_builder::UninitializedFieldError;
+use std::{error, fmt, path::Path};
+
+/// The range of values accepted by `nice(3)`.
+const NICE_RANGE: std::ops::RangeInclusive<i8> = -20..=20;
+
+/// A single rule that a job failed to satisfy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// [`label`](crate::LaunchAgent::label) is empty.
+    EmptyLabel,
+
+    /// None of [`program`](crate::LaunchAgent::program),
+    /// [`program_arguments`](crate::LaunchAgent::program_arguments) or
+    /// [`bundle_program`](crate::LaunchAgent::bundle_program) is set, so
+    /// `launchd` has nothing to execute.
+    MissingExecutable,
+
+    /// [`program`](crate::LaunchAgent::program) is not an absolute path.
+    RelativeProgram(String),
+
+    /// [`program_arguments`](crate::LaunchAgent::program_arguments) is set
+    /// but contains no elements.
+    EmptyProgramArguments,
+
+    /// [`nice`](crate::LaunchAgent::nice) is outside of the -20 to 20 range
+    /// accepted by `nice(3)`.
+    NiceOutOfRange(i8),
+
+    /// A field of a [`CalendarInterval`](crate::CalendarInterval) lies
+    /// outside of the range `launchd` accepts.
+    CalendarFieldOutOfRange {
+        field: &'static str,
+        value: u32,
+        min: u32,
+        max: u32,
+    },
+
+    /// A required builder field was never set.
+    UninitializedField(&'static str),
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyLabel => write!(f, "Label must not be empty"),
+            Self::MissingExecutable => write!(
+                f,
+                "one of Program, ProgramArguments or BundleProgram must be set"
+            ),
+            Self::RelativeProgram(program) => {
+                write!(f, "Program must be an absolute path, got {program:?}")
+            }
+            Self::EmptyProgramArguments => write!(f, "ProgramArguments must not be empty"),
+            Self::NiceOutOfRange(nice) => write!(
+                f,
+                "Nice must be between {} and {}, got {nice}",
+                NICE_RANGE.start(),
+                NICE_RANGE.end()
+            ),
+            Self::CalendarFieldOutOfRange {
+                field,
+                value,
+                min,
+                max,
+            } => write!(
+                f,
+                "calendar interval {field} must be between {min} and {max}, got {value}"
+            ),
+            Self::UninitializedField(field) => write!(f, "{field} must be initialized"),
+        }
+    }
+}
+
+/// Every rule a job failed to satisfy while being validated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationError {
+    issues: Vec<ValidationIssue>,
+}
+
+impl ValidationError {
+    /// Collects `issues`, or returns `None` if there are none.
+    pub(crate) fn from_issues(issues: Vec<ValidationIssue>) -> Option<Self> {
+        (!issues.is_empty()).then_some(Self { issues })
+    }
+
+    /// The rules that failed, in the order they were checked.
+    pub fn issues(&self) -> &[ValidationIssue] {
+        &self.issues
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid LaunchAgent: ")?;
+
+        for (index, issue) in self.issues.iter().enumerate() {
+            if index > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{issue}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl error::Error for ValidationError {}
+
+impl From<UninitializedFieldError> for ValidationError {
+    fn from(error: UninitializedFieldError) -> Self {
+        Self {
+            issues: vec![ValidationIssue::UninitializedField(error.field_name())],
+        }
+    }
+}
+
+/// The subset of a job's keys that determine whether it can be loaded.
+pub(crate) struct Executable<'a> {
+    pub label: &'a str,
+    pub program: Option<&'a str>,
+    pub program_arguments: Option<&'a [String]>,
+    pub bundle_program: Option<&'a str>,
+    pub nice: Option<i8>,
+}
+
+impl Executable<'_> {
+    /// Checks every rule, collecting all of the failures rather than stopping
+    /// at the first.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut issues = Vec::new();
+
+        if self.label.is_empty() {
+            issues.push(ValidationIssue::EmptyLabel);
+        }
+
+        if self.program.is_none()
+            && self.program_arguments.is_none()
+            && self.bundle_program.is_none()
+        {
+            issues.push(ValidationIssue::MissingExecutable);
+        }
+
+        if let Some(program) = self.program
+            && !Path::new(program).is_absolute()
+        {
+            issues.push(ValidationIssue::RelativeProgram(program.to_string()));
+        }
+
+        if self.program_arguments.is_some_and(<[String]>::is_empty) {
+            issues.push(ValidationIssue::EmptyProgramArguments);
+        }
+
+        if let Some(nice) = self.nice
+            && !NICE_RANGE.contains(&nice)
+        {
+            issues.push(ValidationIssue::NiceOutOfRange(nice));
+        }
+
+        ValidationError::from_issues(issues).map_or(Ok(()), Err)
+    }
+}